@@ -0,0 +1,137 @@
+//! A tri-state that can be shared between threads without locking.
+//!
+//! ```rust
+//! use std::sync::atomic::Ordering;
+//! use tristate::TriState;
+//! use tristate::atomic::AtomicTriState;
+//!
+//! static TRACING: AtomicTriState = AtomicTriState::new(TriState::Default);
+//!
+//! TRACING.store(TriState::True, Ordering::Release);
+//! assert_eq!(TRACING.fetch_and(TriState::Default, Ordering::AcqRel), TriState::True);
+//! assert_eq!(TRACING.load(Ordering::Acquire), TriState::Default);
+//! assert_eq!(TRACING.fetch_or(TriState::True, Ordering::AcqRel), TriState::Default);
+//! assert_eq!(TRACING.fetch_not(Ordering::AcqRel), TriState::True);
+//! assert_eq!(TRACING.load(Ordering::Acquire), TriState::False);
+//! ```
+
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::Ordering;
+
+#[cfg(loom)]
+use loom::sync::atomic::AtomicU8;
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicU8;
+
+use crate::TriState;
+
+// Encoded so that the numeric order matches `False < Default < True`, which makes the strong
+// Kleene conjunction and disjunction the atomic minimum and maximum
+const fn encode(value: TriState) -> u8 {
+    match value {
+        TriState::False => 0,
+        TriState::Default => 1,
+        TriState::True => 2
+    }
+}
+
+fn decode(value: u8) -> TriState {
+    match value {
+        0 => TriState::False,
+        1 => TriState::Default,
+        _ => TriState::True
+    }
+}
+
+/// A `TriState` that can be safely shared between threads, backed by an `AtomicU8`
+///
+/// The `fetch_` operations follow strong Kleene logic, matching the operators on `TriState`.
+pub struct AtomicTriState {
+    value: AtomicU8
+}
+
+impl AtomicTriState {
+    /// Returns a new atomic tri-state holding `value`
+    #[cfg(not(loom))]
+    pub const fn new(value: TriState) -> Self {
+        Self { value: AtomicU8::new(encode(value)) }
+    }
+
+    /// Returns a new atomic tri-state holding `value`
+    #[cfg(loom)]
+    pub fn new(value: TriState) -> Self {
+        Self { value: AtomicU8::new(encode(value)) }
+    }
+
+    /// Loads the value
+    pub fn load(&self, order: Ordering) -> TriState {
+        decode(self.value.load(order))
+    }
+
+    /// Stores a value
+    pub fn store(&self, value: TriState, order: Ordering) {
+        self.value.store(encode(value), order)
+    }
+
+    /// Stores a value, returning the previous value
+    pub fn swap(&self, value: TriState, order: Ordering) -> TriState {
+        decode(self.value.swap(encode(value), order))
+    }
+
+    /// Stores `new` if the current value is `current`, returning the previous value wrapped in
+    /// `Ok` if it was stored or `Err` if it was not
+    pub fn compare_exchange(
+        &self,
+        current: TriState,
+        new: TriState,
+        success: Ordering,
+        failure: Ordering
+    ) -> Result<TriState, TriState> {
+        self.value.compare_exchange(encode(current), encode(new), success, failure)
+            .map(decode)
+            .map_err(decode)
+    }
+
+    /// Replaces the value with its strong Kleene conjunction with `value`, returning the
+    /// previous value
+    pub fn fetch_and(&self, value: TriState, order: Ordering) -> TriState {
+        decode(self.value.fetch_min(encode(value), order))
+    }
+
+    /// Replaces the value with its strong Kleene disjunction with `value`, returning the
+    /// previous value
+    pub fn fetch_or(&self, value: TriState, order: Ordering) -> TriState {
+        decode(self.value.fetch_max(encode(value), order))
+    }
+
+    /// Negates the value, leaving `TriState::Default` unchanged, and returns the previous value
+    pub fn fetch_not(&self, order: Ordering) -> TriState {
+        // The strongest ordering a load may use, as it cannot release
+        let load = match order {
+            Ordering::Release | Ordering::Relaxed => Ordering::Relaxed,
+            Ordering::AcqRel => Ordering::Acquire,
+            _ => order
+        };
+        let previous = self.value.fetch_update(order, load, |value| Some(encode(!decode(value))));
+        // The closure never declines to update, so this is always `Ok`
+        decode(previous.unwrap_or_else(|value| value))
+    }
+}
+
+impl Default for AtomicTriState {
+    fn default() -> Self {
+        Self::new(TriState::Default)
+    }
+}
+
+impl From<TriState> for AtomicTriState {
+    fn from(value: TriState) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Debug for AtomicTriState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
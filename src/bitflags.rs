@@ -0,0 +1,272 @@
+//! Integration with [`bitflags`](https://docs.rs/bitflags) for sets of tri-state permissions,
+//! available with the `bitflags` feature.
+//!
+//! A [`TriFlags`] holds an allow, deny or inherit setting for each flag of a bitflags type, in
+//! the way that channel overwrites set permissions in chat applications. [`Overwrites`] applies
+//! such settings in layers on top of a base set of flags and explains which layer decided each
+//! flag.
+//!
+//! ```rust
+//! use bitflags::bitflags;
+//! use tristate::TriState;
+//! use tristate::bitflags::{Layer, Overwrites, TriFlags};
+//!
+//! bitflags! {
+//!     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+//!     struct Permissions: u8 {
+//!         const VIEW = 1;
+//!         const SEND = 1 << 1;
+//!         const PIN = 1 << 2;
+//!     }
+//! }
+//!
+//! let muted = TriFlags::new().deny(Permissions::SEND);
+//! assert_eq!(muted.get(Permissions::SEND), TriState::False);
+//! assert_eq!(muted.get(Permissions::VIEW), TriState::Default);
+//!
+//! let overwrites = Overwrites::new(Permissions::VIEW | Permissions::SEND)
+//!     .role(TriFlags::new().allow(Permissions::PIN))
+//!     .member(muted);
+//! assert_eq!(overwrites.resolve(), Permissions::VIEW | Permissions::PIN);
+//! assert_eq!(overwrites.decide(Permissions::SEND), (false, Layer::Member));
+//! ```
+
+use ::bitflags::Flags;
+
+use crate::TriState;
+
+/// A set of flags of type `P` that are each allowed, denied or inherited
+///
+/// The set is stored as two disjoint bitmasks of the allowed and the denied flags, and a flag in
+/// neither is inherited.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TriFlags<P> {
+    allowed: P,
+    denied: P
+}
+
+impl<P: Flags + Copy> TriFlags<P> {
+    /// Returns a set that inherits every flag
+    pub fn new() -> Self {
+        Self { allowed: P::empty(), denied: P::empty() }
+    }
+
+    /// Returns a set with the given allowed and denied flags, where flags in both are denied
+    pub fn from_masks(allowed: P, denied: P) -> Self {
+        Self { allowed: allowed.difference(denied), denied }
+    }
+
+    /// Returns the set with the given flags allowed
+    pub fn allow(mut self, flags: P) -> Self {
+        self.set(flags, TriState::True);
+        self
+    }
+
+    /// Returns the set with the given flags denied
+    pub fn deny(mut self, flags: P) -> Self {
+        self.set(flags, TriState::False);
+        self
+    }
+
+    /// Returns the set with the given flags inherited
+    pub fn inherit(mut self, flags: P) -> Self {
+        self.set(flags, TriState::Default);
+        self
+    }
+
+    /// Returns the allowed flags
+    pub fn allowed(&self) -> P {
+        self.allowed
+    }
+
+    /// Returns the denied flags
+    pub fn denied(&self) -> P {
+        self.denied
+    }
+
+    /// Returns true if every flag is inherited
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty() && self.denied.is_empty()
+    }
+
+    /// Returns the setting of the given flags, which is `TriState::False` if any of them is
+    /// denied, `TriState::True` if all of them are allowed, and `TriState::Default` otherwise
+    pub fn get(&self, flags: P) -> TriState {
+        if self.denied.intersects(flags) {
+            TriState::False
+        } else if self.allowed.contains(flags) {
+            TriState::True
+        } else {
+            TriState::Default
+        }
+    }
+
+    /// Sets each of the given flags to a value
+    pub fn set(&mut self, flags: P, value: TriState) {
+        self.allowed.set(flags, value == TriState::True);
+        self.denied.set(flags, value == TriState::False);
+    }
+
+    /// Returns `base` with the denied flags removed and the allowed flags added
+    pub fn apply(&self, base: P) -> P {
+        base.difference(self.denied).union(self.allowed)
+    }
+
+    /// Returns this set with `other` applied on top, so that the flags `other` allows or denies
+    /// take its setting and the rest keep their setting here
+    pub fn overlay(self, other: TriFlags<P>) -> Self {
+        Self {
+            allowed: self.allowed.difference(other.denied).union(other.allowed),
+            denied: self.denied.difference(other.allowed).union(other.denied)
+        }
+    }
+
+    /// Returns an iterator over the named flags of `P` and their settings
+    ///
+    /// ```rust
+    /// use bitflags::bitflags;
+    /// use tristate::TriState;
+    /// use tristate::bitflags::TriFlags;
+    ///
+    /// bitflags! {
+    ///     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    ///     struct Access: u8 {
+    ///         const READ = 1;
+    ///         const WRITE = 1 << 1;
+    ///         const DELETE = 1 << 2;
+    ///     }
+    /// }
+    ///
+    /// let flags = TriFlags::new().allow(Access::READ).deny(Access::DELETE);
+    /// assert!(flags.iter().eq([
+    ///     ("READ", Access::READ, TriState::True),
+    ///     ("WRITE", Access::WRITE, TriState::Default),
+    ///     ("DELETE", Access::DELETE, TriState::False)
+    /// ]));
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, P, TriState)> + '_ {
+        P::FLAGS.iter()
+            .filter(|flag| flag.is_named())
+            .map(move |flag| (flag.name(), *flag.value(), self.get(*flag.value())))
+    }
+}
+
+impl<P: Flags + Copy> Default for TriFlags<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A layer of [`Overwrites`], in the order they are applied
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Layer {
+    /// The base flags, such as those granted by roles
+    Base,
+    /// The overwrites for roles
+    Role,
+    /// The overwrites for the member
+    Member
+}
+
+/// Flags resolved from a base set and layers of overwrites
+///
+/// The role overwrites are merged so that a flag any of them allows is allowed, and otherwise a
+/// flag any of them denies is denied. They are applied to the base flags, and the member
+/// overwrites are applied on top of the result, later ones taking precedence.
+///
+/// ```rust
+/// use bitflags::bitflags;
+/// use tristate::bitflags::{Layer, Overwrites, TriFlags};
+///
+/// bitflags! {
+///     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+///     struct Permissions: u8 {
+///         const VIEW = 1;
+///         const SEND = 1 << 1;
+///         const PIN = 1 << 2;
+///     }
+/// }
+///
+/// let overwrites = Overwrites::new(Permissions::VIEW)
+///     .role(TriFlags::new().deny(Permissions::VIEW | Permissions::SEND))
+///     .role(TriFlags::new().allow(Permissions::SEND))
+///     .member(TriFlags::new().allow(Permissions::PIN))
+///     .member(TriFlags::new().deny(Permissions::PIN));
+///
+/// assert!(overwrites.explain().eq([
+///     ("VIEW", Permissions::VIEW, false, Layer::Role),
+///     ("SEND", Permissions::SEND, true, Layer::Role),
+///     ("PIN", Permissions::PIN, false, Layer::Member)
+/// ]));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Overwrites<P> {
+    base: P,
+    role: TriFlags<P>,
+    member: TriFlags<P>
+}
+
+impl<P: Flags + Copy> Overwrites<P> {
+    /// Returns the base flags without overwrites
+    pub fn new(base: P) -> Self {
+        Self { base, role: TriFlags::new(), member: TriFlags::new() }
+    }
+
+    /// Returns the overwrites with those of another role merged in
+    pub fn role(mut self, overwrite: TriFlags<P>) -> Self {
+        let allowed = self.role.allowed.union(overwrite.allowed);
+        self.role = TriFlags::from_masks(allowed, self.role.denied.union(overwrite.denied).difference(allowed));
+        self
+    }
+
+    /// Returns the overwrites with a member overwrite applied on top of the previous ones
+    pub fn member(mut self, overwrite: TriFlags<P>) -> Self {
+        self.member = self.member.overlay(overwrite);
+        self
+    }
+
+    /// Returns the base flags
+    pub fn base(&self) -> P {
+        self.base
+    }
+
+    /// Returns the merged role overwrites
+    pub fn role_overwrites(&self) -> TriFlags<P> {
+        self.role
+    }
+
+    /// Returns the merged member overwrites
+    pub fn member_overwrites(&self) -> TriFlags<P> {
+        self.member
+    }
+
+    /// Returns the flags after applying every layer
+    pub fn resolve(&self) -> P {
+        self.member.apply(self.role.apply(self.base))
+    }
+
+    /// Returns whether all of the given flags are set after applying every layer, and the last
+    /// layer that set any of them
+    pub fn decide(&self, flags: P) -> (bool, Layer) {
+        let touches = |overwrite: &TriFlags<P>| overwrite.allowed.union(overwrite.denied).intersects(flags);
+        let layer = if touches(&self.member) {
+            Layer::Member
+        } else if touches(&self.role) {
+            Layer::Role
+        } else {
+            Layer::Base
+        };
+        (self.resolve().contains(flags), layer)
+    }
+
+    /// Returns an iterator over the named flags of `P`, whether each is set after applying every
+    /// layer, and the layer that decided it
+    pub fn explain(&self) -> impl Iterator<Item = (&'static str, P, bool, Layer)> + '_ {
+        P::FLAGS.iter()
+            .filter(|flag| flag.is_named())
+            .map(move |flag| {
+                let (set, layer) = self.decide(*flag.value());
+                (flag.name(), *flag.value(), set, layer)
+            })
+    }
+}
@@ -0,0 +1,152 @@
+//! Resolution of a tri-state through ordered layers of settings.
+//!
+//! Settings are often gathered from several places, such as the command line, the environment
+//! and configuration files, with earlier places taking priority. A [`Cascade`] holds one named
+//! `TriState` per place and resolves to the first definite value, remembering which layer
+//! decided it.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::cascade::{Cascade, Source};
+//!
+//! let color = Cascade::new()
+//!     .with("--color", TriState::Default)
+//!     .with("$COLOR", TriState::Default)
+//!     .with("$PROJECT/.config", TriState::True)
+//!     .with("~/.config", TriState::False);
+//!
+//! let resolution = color.resolve_or(false);
+//! assert!(resolution.value);
+//! assert_eq!(resolution.source, Source::Layer(&"$PROJECT/.config"));
+//! assert_eq!(format!("color {}", resolution), "color enabled by $PROJECT/.config");
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::iter::FromIterator;
+
+use crate::TriState;
+
+/// An ordered list of named tri-state layers, where earlier layers take priority
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Cascade<N> {
+    layers: Vec<(N, TriState)>
+}
+
+impl<N> Cascade<N> {
+    /// Returns a new cascade without any layers
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Returns the cascade with a layer added below the existing ones
+    pub fn with(mut self, name: N, value: TriState) -> Self {
+        self.push(name, value);
+        self
+    }
+
+    /// Adds a layer below the existing ones
+    pub fn push(&mut self, name: N, value: TriState) {
+        self.layers.push((name, value));
+    }
+
+    /// Returns an iterator over the layers, from highest to lowest priority
+    pub fn layers(&self) -> impl Iterator<Item = (&N, TriState)> {
+        self.layers.iter().map(|(name, value)| (name, *value))
+    }
+
+    /// Returns the first definite value, or `TriState::Default` if no layer has one
+    pub fn value(&self) -> TriState {
+        self.resolve().map_or(TriState::Default, |resolution| TriState::from(resolution.value))
+    }
+
+    /// Returns the first definite value along with the layer that decided it, or `None` if no
+    /// layer has one
+    pub fn resolve(&self) -> Option<Resolution<'_, N>> {
+        self.layers.iter().find_map(|(name, value)| {
+            Option::<bool>::from(value).map(|value| Resolution { value, source: Source::Layer(name) })
+        })
+    }
+
+    /// Returns the first definite value along with the layer that decided it, falling back to
+    /// `fallback` if no layer has one
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::cascade::{Cascade, Source};
+    ///
+    /// let cascade = Cascade::new().with("cli", TriState::Default);
+    /// let resolution = cascade.resolve_or(true);
+    ///
+    /// assert!(resolution.value);
+    /// assert_eq!(resolution.source, Source::Fallback);
+    /// assert_eq!(resolution.to_string(), "enabled by default");
+    /// ```
+    pub fn resolve_or(&self, fallback: bool) -> Resolution<'_, N> {
+        self.resolve().unwrap_or(Resolution { value: fallback, source: Source::Fallback })
+    }
+}
+
+impl<N> Default for Cascade<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> FromIterator<(N, TriState)> for Cascade<N> {
+    fn from_iter<I: IntoIterator<Item = (N, TriState)>>(iter: I) -> Self {
+        Self { layers: iter.into_iter().collect() }
+    }
+}
+
+impl<N> Extend<(N, TriState)> for Cascade<N> {
+    fn extend<I: IntoIterator<Item = (N, TriState)>>(&mut self, iter: I) {
+        self.layers.extend(iter);
+    }
+}
+
+/// The value a [`Cascade`] resolved to and where it came from
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct Resolution<'a, N> {
+    /// The resolved value
+    pub value: bool,
+    /// Where the value came from
+    pub source: Source<'a, N>
+}
+
+// Implemented by hand since deriving would require `N: Clone` even though only `&N` is held
+impl<N> Clone for Resolution<'_, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for Resolution<'_, N> {}
+
+impl<N: Display> Display for Resolution<'_, N> {
+    /// Formats the resolution as an explanation such as `enabled by $PROJECT/.config`
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let state = if self.value { "enabled" } else { "disabled" };
+        match self.source {
+            Source::Layer(name) => write!(f, "{} by {}", state, name),
+            Source::Fallback => write!(f, "{} by default", state)
+        }
+    }
+}
+
+/// Where the value of a [`Resolution`] came from
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub enum Source<'a, N> {
+    /// The named layer held the first definite value
+    Layer(&'a N),
+    /// No layer held a definite value, so the fallback was used
+    Fallback
+}
+
+impl<N> Clone for Source<'_, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for Source<'_, N> {}
@@ -0,0 +1,380 @@
+//! Trees of tri-state checkboxes.
+//!
+//! A [`CheckTree`] holds nested checkboxes whose states are tri-states, where `TriState::True`
+//! is checked, `TriState::False` is unchecked and `TriState::Default` is mixed. Setting a node
+//! sets all of its descendants to the same state, and every ancestor is checked or unchecked
+//! when all of its children agree, and mixed otherwise. Every edit is logged and can be undone.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::check_tree::CheckTree;
+//!
+//! let mut tree = CheckTree::new();
+//! let network = tree.insert(None, "network", false);
+//! let wifi = tree.insert(Some(network), "wifi", false);
+//! let ethernet = tree.insert(Some(network), "ethernet", false);
+//!
+//! tree.set(wifi, true);
+//! assert_eq!(tree.state(network), Some(TriState::Default));
+//!
+//! tree.set(network, true);
+//! assert_eq!(tree.state(ethernet), Some(TriState::True));
+//! assert_eq!(tree.checked_leaves().map(|(_, name)| *name).collect::<Vec<_>>(), ["wifi", "ethernet"]);
+//!
+//! tree.undo();
+//! assert_eq!(tree.state(network), Some(TriState::Default));
+//! assert_eq!(tree.state(ethernet), Some(TriState::False));
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::TriState;
+
+/// The identifier of a node in a [`CheckTree`]
+///
+/// Identifiers are never reused within a tree, so the identifier of a removed node stays
+/// unused, and refers to the node again if its removal is undone.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct NodeId(usize);
+
+/// A change to the state of a node in a [`CheckTree`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Change {
+    /// The node that changed
+    pub node: NodeId,
+    /// The state before the change
+    pub previous: TriState,
+    /// The state after the change
+    pub current: TriState
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    value: T,
+    state: TriState,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>
+}
+
+#[derive(Clone, Debug)]
+enum Edit<T> {
+    Set,
+    Insert(NodeId),
+    Remove {
+        parent: Option<NodeId>,
+        position: usize,
+        nodes: Vec<(NodeId, Node<T>)>
+    }
+}
+
+/// A tree of checkboxes whose states are tri-states
+#[derive(Clone, Debug)]
+pub struct CheckTree<T> {
+    nodes: Vec<Option<Node<T>>>,
+    roots: Vec<NodeId>,
+    history: Vec<(Edit<T>, Vec<Change>)>
+}
+
+impl<T> CheckTree<T> {
+    /// Returns an empty tree
+    pub fn new() -> Self {
+        Self { nodes: Vec::new(), roots: Vec::new(), history: Vec::new() }
+    }
+
+    /// Returns the number of nodes in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.iter().filter(|node| node.is_some()).count()
+    }
+
+    /// Returns true if the tree has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns true if the node is in the tree
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    /// Returns the value of a node
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).map(|node| &node.value)
+    }
+
+    /// Returns a mutable reference to the value of a node
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.nodes.get_mut(id.0).and_then(Option::as_mut).map(|node| &mut node.value)
+    }
+
+    /// Returns the state of a node
+    pub fn state(&self, id: NodeId) -> Option<TriState> {
+        self.node(id).map(|node| node.state)
+    }
+
+    /// Returns the parent of a node, which is `None` for roots and nodes not in the tree
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).and_then(|node| node.parent)
+    }
+
+    /// Returns the children of a node in order
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[], |node| &node.children)
+    }
+
+    /// Returns the roots of the tree in order
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// Returns an iterator over the nodes in depth-first order, each parent before its children
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        let mut stack: Vec<NodeId> = self.roots.iter().rev().copied().collect();
+        core::iter::from_fn(move || {
+            let id = stack.pop()?;
+            let node = self.node(id)?;
+            stack.extend(node.children.iter().rev());
+            Some((id, &node.value))
+        })
+    }
+
+    /// Returns an iterator over the checked nodes without children in depth-first order
+    pub fn checked_leaves(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.iter().filter(move |&(id, _)| self.node(id).is_some_and(|node| node.children.is_empty() && node.state == TriState::True))
+    }
+
+    /// Inserts a node as the last child of `parent`, or as the last root if `parent` is `None`,
+    /// and returns its identifier
+    ///
+    /// The states of the ancestors of the node are updated to account for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is not in the tree.
+    pub fn insert(&mut self, parent: Option<NodeId>, value: T, checked: bool) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        match parent {
+            Some(parent) => self.node_mut(parent).children.push(id),
+            None => self.roots.push(id)
+        }
+        self.nodes.push(Some(Node { value, state: checked.into(), parent, children: Vec::new() }));
+
+        let mut changes = Vec::new();
+        self.update_ancestors(id, &mut changes);
+        self.history.push((Edit::Insert(id), changes));
+        id
+    }
+
+    /// Removes a node and its descendants from the tree, and returns true if it was in the tree
+    ///
+    /// The states of the ancestors of the node are updated to account for it. A parent left
+    /// without children keeps its state, unless it was mixed, in which case it is unchecked.
+    /// The removed nodes are kept in the history so the removal can be undone.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::check_tree::CheckTree;
+    ///
+    /// let mut tree = CheckTree::new();
+    /// let parent = tree.insert(None, "parent", true);
+    /// let child = tree.insert(Some(parent), "child", true);
+    /// let grandchild = tree.insert(Some(child), "grandchild", false);
+    /// tree.insert(Some(parent), "sibling", true);
+    /// assert_eq!(tree.state(parent), Some(TriState::Default));
+    ///
+    /// assert!(tree.remove(child));
+    /// assert_eq!(tree.state(parent), Some(TriState::True));
+    /// assert!(!tree.contains(grandchild));
+    ///
+    /// tree.undo();
+    /// assert_eq!(tree.state(parent), Some(TriState::Default));
+    /// assert_eq!(tree.children(parent)[0], child);
+    /// assert_eq!(tree.get(grandchild), Some(&"grandchild"));
+    /// ```
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let parent = match self.node(id) {
+            Some(node) => node.parent,
+            None => return false
+        };
+        let siblings = match parent {
+            Some(parent) => &mut self.node_mut(parent).children,
+            None => &mut self.roots
+        };
+        let position = siblings.iter().position(|&sibling| sibling == id).expect("node is a child of its parent");
+        siblings.remove(position);
+
+        let mut nodes = Vec::new();
+        let mut stack = alloc::vec![id];
+        while let Some(id) = stack.pop() {
+            let node = self.nodes[id.0].take().expect("descendant is in the tree");
+            stack.extend(&node.children);
+            nodes.push((id, node));
+        }
+
+        let mut changes = Vec::new();
+        if let Some(parent) = parent {
+            self.recompute(parent, &mut changes);
+            self.update_ancestors(parent, &mut changes);
+        }
+        self.history.push((Edit::Remove { parent, position, nodes }, changes));
+        true
+    }
+
+    /// Checks or unchecks a node and all of its descendants, updates the states of its
+    /// ancestors, and returns the changes made
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is not in the tree.
+    pub fn set(&mut self, id: NodeId, checked: bool) -> &[Change] {
+        let mut changes = Vec::new();
+        let mut stack = alloc::vec![id];
+        while let Some(id) = stack.pop() {
+            self.set_state(id, checked.into(), &mut changes);
+            stack.extend(&self.node_mut(id).children);
+        }
+        self.update_ancestors(id, &mut changes);
+
+        if changes.is_empty() {
+            return &[];
+        }
+        self.history.push((Edit::Set, changes));
+        &self.history[self.history.len() - 1].1
+    }
+
+    /// Checks a node that is unchecked or mixed and unchecks a node that is checked, as a click
+    /// on the checkbox would, and returns the changes made
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::check_tree::CheckTree;
+    ///
+    /// let mut tree = CheckTree::new();
+    /// let parent = tree.insert(None, (), true);
+    /// tree.insert(Some(parent), (), true);
+    /// tree.insert(Some(parent), (), false);
+    /// assert_eq!(tree.state(parent), Some(TriState::Default));
+    ///
+    /// assert_eq!(tree.toggle(parent).len(), 2);
+    /// assert_eq!(tree.state(parent), Some(TriState::True));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is not in the tree.
+    pub fn toggle(&mut self, id: NodeId) -> &[Change] {
+        let checked = self.node_mut(id).state != TriState::True;
+        self.set(id, checked)
+    }
+
+    /// Returns an iterator over the state changes in the history, oldest first
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::check_tree::{Change, CheckTree};
+    ///
+    /// let mut tree = CheckTree::new();
+    /// let parent = tree.insert(None, (), false);
+    /// let child = tree.insert(Some(parent), (), false);
+    /// tree.set(child, true);
+    ///
+    /// assert!(tree.changes().eq(&[
+    ///     Change { node: child, previous: TriState::False, current: TriState::True },
+    ///     Change { node: parent, previous: TriState::False, current: TriState::True }
+    /// ]));
+    /// ```
+    pub fn changes(&self) -> impl Iterator<Item = &Change> {
+        self.history.iter().flat_map(|(_, changes)| changes)
+    }
+
+    /// Returns true if there is an edit to undo
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    /// Undoes the last insertion, removal or change of state, and returns true if there was
+    /// one
+    pub fn undo(&mut self) -> bool {
+        let (edit, changes) = match self.history.pop() {
+            Some(entry) => entry,
+            None => return false
+        };
+        for change in changes.iter().rev() {
+            self.node_mut(change.node).state = change.previous;
+        }
+        match edit {
+            Edit::Set => {}
+            Edit::Insert(id) => {
+                let node = self.nodes[id.0].take().expect("inserted node is in the tree");
+                match node.parent {
+                    Some(parent) => self.node_mut(parent).children.retain(|&child| child != id),
+                    None => self.roots.retain(|&root| root != id)
+                }
+            }
+            Edit::Remove { parent, position, nodes } => {
+                let id = nodes[0].0;
+                match parent {
+                    Some(parent) => self.node_mut(parent).children.insert(position, id),
+                    None => self.roots.insert(position, id)
+                }
+                for (id, node) in nodes {
+                    self.nodes[id.0] = Some(node);
+                }
+            }
+        }
+        true
+    }
+
+    /// Forgets the history, dropping the nodes that were removed
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id.0).and_then(Option::as_ref)
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node<T> {
+        self.nodes.get_mut(id.0).and_then(Option::as_mut).expect("node is not in the tree")
+    }
+
+    fn set_state(&mut self, id: NodeId, state: TriState, changes: &mut Vec<Change>) {
+        let node = self.node_mut(id);
+        if node.state != state {
+            changes.push(Change { node: id, previous: node.state, current: state });
+            node.state = state;
+        }
+    }
+
+    /// Sets the state of a node from the states of its children
+    fn recompute(&mut self, id: NodeId, changes: &mut Vec<Change>) {
+        let node = self.node(id).expect("node is not in the tree");
+        let mut states = node.children.iter().map(|&child| self.nodes[child.0].as_ref().expect("child is in the tree").state);
+        let state = match states.next() {
+            Some(first) if states.all(|state| state == first) => first,
+            Some(_) => TriState::Default,
+            None if node.state == TriState::Default => TriState::False,
+            None => node.state
+        };
+        self.set_state(id, state, changes);
+    }
+
+    /// Recomputes the states of the ancestors of a node, stopping at the first that is
+    /// unchanged
+    fn update_ancestors(&mut self, id: NodeId, changes: &mut Vec<Change>) {
+        let mut current = self.node_mut(id).parent;
+        while let Some(id) = current {
+            let len = changes.len();
+            self.recompute(id, changes);
+            if changes.len() == len {
+                break;
+            }
+            current = self.node_mut(id).parent;
+        }
+    }
+}
+
+impl<T> Default for CheckTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
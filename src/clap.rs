@@ -0,0 +1,228 @@
+//! Integration with [`clap`](https://docs.rs/clap) for command line arguments, available with the
+//! `clap` feature.
+//!
+//! `TriState` implements `ValueEnum` and `ValueParserFactory`, so it can be used directly as the
+//! type of an argument taking `true`, `false` or `default` (or any of the other spellings accepted
+//! by `TriState::from_str`).
+//!
+//! More commonly a tri-state is given as a pair of flags such as `--color` and `--no-color`,
+//! where leaving both off means `TriState::Default`. A [`FlagPair`] builds those arguments and
+//! reads the tri-state back, and the [`flag_pair!`](crate::flag_pair) macro wraps one into a type
+//! that can be flattened into a derived parser.
+//!
+//! ```rust
+//! use clap::Parser;
+//! use tristate::TriState;
+//!
+//! tristate::flag_pair!(
+//!     /// Whether to color the output
+//!     pub struct Color = "color"
+//! );
+//!
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[command(flatten)]
+//!     color: Color,
+//!     #[arg(long, value_enum, default_value_t = TriState::Default)]
+//!     unicode: TriState
+//! }
+//!
+//! let cli = Cli::parse_from(["app", "--no-color", "--color", "--unicode", "off"]);
+//! assert_eq!(cli.color.0, TriState::True);
+//! assert_eq!(cli.unicode, TriState::False);
+//!
+//! let cli = Cli::parse_from(["app"]);
+//! assert_eq!(cli.color.0, TriState::Default);
+//! ```
+
+use std::format;
+use std::string::String;
+
+use ::clap::builder::{EnumValueParser, PossibleValue, ValueParserFactory};
+use ::clap::{Arg, ArgAction, ArgMatches, ValueEnum};
+
+use crate::TriState;
+
+#[doc(hidden)]
+pub use ::clap as __clap;
+
+impl ValueEnum for TriState {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[TriState::True, TriState::False, TriState::Default]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            TriState::True => PossibleValue::new("true").aliases(["yes", "on", "1"]),
+            TriState::False => PossibleValue::new("false").aliases(["no", "off", "0"]),
+            TriState::Default => PossibleValue::new("default").aliases(["auto", "inherit", "-"])
+        })
+    }
+}
+
+impl ValueParserFactory for TriState {
+    type Parser = EnumValueParser<TriState>;
+
+    fn value_parser() -> Self::Parser {
+        EnumValueParser::new()
+    }
+}
+
+/// A pair of flags such as `--color` and `--no-color` that together give a tri-state
+///
+/// Giving the positive flag yields `TriState::True`, the negative flag `TriState::False`, and
+/// neither `TriState::Default`. When both are given the last one wins, unless the pair is
+/// strict, in which case clap reports a conflict.
+///
+/// ```rust
+/// use clap::Command;
+/// use tristate::TriState;
+/// use tristate::clap::FlagPair;
+///
+/// let color = FlagPair::new("color").help("Color the output");
+/// let command = Command::new("app").args(color.args());
+///
+/// let matches = command.clone().get_matches_from(["app", "--color", "--no-color"]);
+/// assert_eq!(color.get(&matches), TriState::False);
+///
+/// let strict = FlagPair::new("color").strict(true);
+/// let command = Command::new("app").args(strict.args());
+/// assert!(command.try_get_matches_from(["app", "--color", "--no-color"]).is_err());
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FlagPair {
+    name: String,
+    help: Option<String>,
+    no_help: Option<String>,
+    strict: bool
+}
+
+impl FlagPair {
+    /// Returns the flag pair `--name` and `--no-name`, which also serve as the argument ids
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self { name: name.into(), help: None, no_help: None, strict: false }
+    }
+
+    /// Returns the flag pair with help text for the positive flag
+    pub fn help<S: Into<String>>(mut self, help: S) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Returns the flag pair with help text for the negative flag
+    pub fn no_help<S: Into<String>>(mut self, help: S) -> Self {
+        self.no_help = Some(help.into());
+        self
+    }
+
+    /// Returns the flag pair set to report a conflict rather than letting the last flag win
+    /// when both are given
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the id and long name of the negative flag
+    pub fn no_name(&self) -> String {
+        format!("no-{}", self.name)
+    }
+
+    /// Returns the two arguments making up the pair
+    pub fn args(&self) -> [Arg; 2] {
+        let no_name = self.no_name();
+        let mut yes = Arg::new(self.name.clone()).long(self.name.clone()).action(ArgAction::SetTrue);
+        let mut no = Arg::new(no_name.clone()).long(no_name.clone()).action(ArgAction::SetTrue);
+        if self.strict {
+            yes = yes.conflicts_with(no_name);
+        } else {
+            yes = yes.overrides_with(no_name);
+            no = no.overrides_with(self.name.clone());
+        }
+        if let Some(help) = &self.help {
+            yes = yes.help(help.clone());
+        }
+        if let Some(help) = &self.no_help {
+            no = no.help(help.clone());
+        }
+        [yes, no]
+    }
+
+    /// Returns the tri-state given by the pair in parsed arguments
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arguments were not parsed by a command holding the pair.
+    pub fn get(&self, matches: &ArgMatches) -> TriState {
+        if matches.get_flag(&self.name) {
+            TriState::True
+        } else if matches.get_flag(&self.no_name()) {
+            TriState::False
+        } else {
+            TriState::Default
+        }
+    }
+}
+
+/// Declares a type wrapping a `TriState` that is parsed from a [`FlagPair`], for flattening
+/// into a parser derived with clap
+///
+/// The type is declared as `struct Name = "flag"`, optionally followed by `, strict` to report
+/// a conflict when both flags are given. Doc comments on the declaration become the help text of
+/// the positive flag.
+#[macro_export]
+macro_rules! flag_pair {
+    ($(#[doc = $doc:literal])* $vis:vis struct $name:ident = $flag:literal) => {
+        $crate::flag_pair!($(#[doc = $doc])* $vis struct $name = $flag, strict = false);
+    };
+    ($(#[doc = $doc:literal])* $vis:vis struct $name:ident = $flag:literal, strict) => {
+        $crate::flag_pair!($(#[doc = $doc])* $vis struct $name = $flag, strict = true);
+    };
+    ($(#[doc = $doc:literal])* $vis:vis struct $name:ident = $flag:literal, strict = $strict:expr) => {
+        $(#[doc = $doc])*
+        #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+        $vis struct $name(pub $crate::TriState);
+
+        impl $name {
+            fn flag_pair() -> $crate::clap::FlagPair {
+                let help: &[&str] = &[$($doc.trim()),*];
+                let pair = $crate::clap::FlagPair::new($flag).strict($strict);
+                if help.is_empty() { pair } else { pair.help(help.join(" ")) }
+            }
+        }
+
+        impl $crate::clap::__clap::FromArgMatches for $name {
+            fn from_arg_matches(
+                matches: &$crate::clap::__clap::ArgMatches
+            ) -> Result<Self, $crate::clap::__clap::Error> {
+                Ok(Self(Self::flag_pair().get(matches)))
+            }
+
+            fn update_from_arg_matches(
+                &mut self,
+                matches: &$crate::clap::__clap::ArgMatches
+            ) -> Result<(), $crate::clap::__clap::Error> {
+                let value = Self::flag_pair().get(matches);
+                if value != $crate::TriState::Default {
+                    self.0 = value;
+                }
+                Ok(())
+            }
+        }
+
+        impl $crate::clap::__clap::Args for $name {
+            fn augment_args(command: $crate::clap::__clap::Command) -> $crate::clap::__clap::Command {
+                command.args(Self::flag_pair().args())
+            }
+
+            fn augment_args_for_update(command: $crate::clap::__clap::Command) -> $crate::clap::__clap::Command {
+                Self::augment_args(command)
+            }
+        }
+
+        impl From<$name> for $crate::TriState {
+            fn from(flag: $name) -> Self {
+                flag.0
+            }
+        }
+    };
+}
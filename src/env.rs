@@ -0,0 +1,184 @@
+//! Reading tri-states from environment variables.
+//!
+//! A variable that is unset or empty gives `TriState::Default`, and any other value is parsed
+//! with `TriState::from_str`. Lookups go through the [`Environment`] trait, so a stand-in
+//! environment can replace the process environment, such as in tests.
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use tristate::TriState;
+//! use tristate::env::scan_prefix;
+//!
+//! let mut env = HashMap::new();
+//! env.insert("APP_COLOR".to_owned(), "on".to_owned());
+//! env.insert("APP_UNICODE".to_owned(), "".to_owned());
+//! env.insert("HOME".to_owned(), "/root".to_owned());
+//!
+//! assert_eq!(TriState::from_env_in(&env, "APP_COLOR"), Ok(TriState::True));
+//! assert_eq!(TriState::from_env_in(&env, "APP_MISSING"), Ok(TriState::Default));
+//!
+//! env.insert("APP_TRACE".to_owned(), "sometimes".to_owned());
+//! let error = TriState::from_env_in(&env, "APP_TRACE").unwrap_err();
+//! assert!(error.to_string().starts_with("environment variable APP_TRACE: invalid tri-state"));
+//! assert!(scan_prefix(&env, "APP").is_err());
+//!
+//! env.remove("APP_TRACE");
+//! let flags = scan_prefix(&env, "APP").unwrap();
+//! assert_eq!(flags.len(), 2);
+//! assert_eq!(flags["COLOR"], TriState::True);
+//! assert_eq!(flags["UNICODE"], TriState::Default);
+//! ```
+
+use std::borrow::ToOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{ParseTriStateError, TriState};
+
+/// A source of environment variables
+pub trait Environment {
+    /// Returns the value of the variable, or `None` if it is unset
+    fn var_os(&self, name: &OsStr) -> Option<OsString>;
+
+    /// Returns every variable along with its value
+    fn vars_os(&self) -> Vec<(OsString, OsString)>;
+}
+
+/// The environment of the current process
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct ProcessEnvironment;
+
+impl Environment for ProcessEnvironment {
+    fn var_os(&self, name: &OsStr) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+
+    fn vars_os(&self) -> Vec<(OsString, OsString)> {
+        std::env::vars_os().collect()
+    }
+}
+
+impl<S: BuildHasher> Environment for HashMap<String, String, S> {
+    fn var_os(&self, name: &OsStr) -> Option<OsString> {
+        self.get(name.to_str()?).map(OsString::from)
+    }
+
+    fn vars_os(&self) -> Vec<(OsString, OsString)> {
+        self.iter().map(|(name, value)| (name.into(), value.into())).collect()
+    }
+}
+
+impl Environment for BTreeMap<String, String> {
+    fn var_os(&self, name: &OsStr) -> Option<OsString> {
+        self.get(name.to_str()?).map(OsString::from)
+    }
+
+    fn vars_os(&self) -> Vec<(OsString, OsString)> {
+        self.iter().map(|(name, value)| (name.into(), value.into())).collect()
+    }
+}
+
+impl<E: Environment + ?Sized> Environment for &E {
+    fn var_os(&self, name: &OsStr) -> Option<OsString> {
+        (**self).var_os(name)
+    }
+
+    fn vars_os(&self) -> Vec<(OsString, OsString)> {
+        (**self).vars_os()
+    }
+}
+
+impl TriState {
+    /// Returns the tri-state held by an environment variable of the current process, which is
+    /// `TriState::Default` if the variable is unset or empty
+    pub fn from_env(name: &str) -> Result<TriState, EnvError> {
+        Self::from_env_in(ProcessEnvironment, name)
+    }
+
+    /// Returns the tri-state held by an environment variable of the current process, for
+    /// variable names that may not be valid unicode
+    pub fn from_env_os<N: AsRef<OsStr>>(name: N) -> Result<TriState, EnvError> {
+        Self::from_env_os_in(ProcessEnvironment, name)
+    }
+
+    /// Returns the tri-state held by a variable of the given environment
+    pub fn from_env_in<E: Environment>(env: E, name: &str) -> Result<TriState, EnvError> {
+        Self::from_env_os_in(env, name)
+    }
+
+    /// Returns the tri-state held by a variable of the given environment, for variable names
+    /// that may not be valid unicode
+    pub fn from_env_os_in<E: Environment, N: AsRef<OsStr>>(env: E, name: N) -> Result<TriState, EnvError> {
+        let name = name.as_ref();
+        match env.var_os(name) {
+            Some(value) => parse(name, value),
+            None => Ok(TriState::Default)
+        }
+    }
+}
+
+fn parse(name: &OsStr, value: OsString) -> Result<TriState, EnvError> {
+    let value = value.into_string().map_err(|_| EnvError::NotUnicode { name: name.to_owned() })?;
+    value.parse().map_err(|source| EnvError::Parse { name: name.to_owned(), source })
+}
+
+/// Returns the tri-states held by every variable named `PREFIX_*` in the given environment,
+/// keyed by the part of the name after `PREFIX_`
+///
+/// Variables whose names are not valid unicode are skipped.
+pub fn scan_prefix<E: Environment>(env: E, prefix: &str) -> Result<BTreeMap<String, TriState>, EnvError> {
+    let mut flags = BTreeMap::new();
+    for (name, value) in env.vars_os() {
+        let key = name.to_str()
+            .and_then(|name| name.strip_prefix(prefix))
+            .and_then(|rest| rest.strip_prefix('_'));
+        if let Some(key) = key {
+            flags.insert(key.to_owned(), parse(&name, value)?);
+        }
+    }
+    Ok(flags)
+}
+
+/// The error returned when an environment variable does not hold a tri-state
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum EnvError {
+    /// The value of the variable is not valid unicode
+    NotUnicode {
+        /// Name of the variable
+        name: OsString
+    },
+    /// The value of the variable is not a tri-state
+    Parse {
+        /// Name of the variable
+        name: OsString,
+        /// The error from parsing the value
+        source: ParseTriStateError
+    }
+}
+
+impl Display for EnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::NotUnicode { name } => {
+                write!(f, "environment variable {} is not valid unicode", name.to_string_lossy())
+            }
+            Self::Parse { name, source } => {
+                write!(f, "environment variable {}: {}", name.to_string_lossy(), source)
+            }
+        }
+    }
+}
+
+impl Error for EnvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NotUnicode { .. } => None,
+            Self::Parse { source, .. } => Some(source)
+        }
+    }
+}
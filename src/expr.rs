@@ -0,0 +1,394 @@
+//! Boolean expressions over variables whose values may not be known yet.
+//!
+//! An [`Expr`] is evaluated against a [`Valuation`], which gives each variable a `TriState`
+//! with `TriState::Default` for a variable that is still unknown. Evaluation follows strong
+//! Kleene logic, so an expression is already definite when its known variables decide it, and
+//! [`Expr::explain`] reports which unknown variables stand in the way otherwise.
+//!
+//! Expressions can also be parsed from text, simplified, and brought into negation, conjunctive
+//! or disjunctive normal form, all without relying on the law of excluded middle.
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//! use tristate::TriState;
+//! use tristate::expr::Expr;
+//!
+//! // (admin || owner) && !suspended
+//! let rule = (Expr::var("admin") | Expr::var("owner")) & !Expr::var("suspended");
+//! assert_eq!(rule.to_string(), "(admin || owner) && !suspended");
+//!
+//! let mut facts = BTreeMap::new();
+//! facts.insert("suspended", TriState::True);
+//! assert_eq!(rule.eval(&facts), TriState::False);
+//!
+//! facts.insert("suspended", TriState::False);
+//! let evaluation = rule.explain(&facts);
+//! assert_eq!(evaluation.value, TriState::Default);
+//! assert!(evaluation.blocking.into_iter().eq([&"admin", &"owner"]));
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::TriState;
+
+mod parse;
+mod simplify;
+
+pub use self::parse::{ParseExprError, ParseExprErrorKind};
+
+/// A boolean expression over variables identified by keys of type `K`
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Expr<K> {
+    /// A constant, where `TriState::Default` is the unknown constant
+    Const(TriState),
+    /// A variable
+    Var(K),
+    /// The negation of an expression
+    Not(Box<Expr<K>>),
+    /// The conjunction of two expressions
+    And(Box<Expr<K>>, Box<Expr<K>>),
+    /// The disjunction of two expressions
+    Or(Box<Expr<K>>, Box<Expr<K>>),
+    /// The exclusive disjunction of two expressions
+    Xor(Box<Expr<K>>, Box<Expr<K>>),
+    /// The implication from the first expression to the second
+    Implies(Box<Expr<K>>, Box<Expr<K>>),
+    /// The equivalence of two expressions
+    Iff(Box<Expr<K>>, Box<Expr<K>>)
+}
+
+impl<K> Expr<K> {
+    /// Returns the expression for a variable
+    pub fn var(key: K) -> Self {
+        Self::Var(key)
+    }
+
+    /// Returns the implication `self -> rhs`
+    pub fn implies(self, rhs: Self) -> Self {
+        Self::Implies(Box::new(self), Box::new(rhs))
+    }
+
+    /// Returns the equivalence `self <-> rhs`
+    pub fn iff(self, rhs: Self) -> Self {
+        Self::Iff(Box::new(self), Box::new(rhs))
+    }
+
+    /// Returns the value of the expression under a valuation
+    pub fn eval<V: Valuation<K> + ?Sized>(&self, valuation: &V) -> TriState {
+        match self {
+            Self::Const(value) => *value,
+            Self::Var(key) => valuation.value(key),
+            Self::Not(inner) => !inner.eval(valuation),
+            Self::And(a, b) => a.eval(valuation) & b.eval(valuation),
+            Self::Or(a, b) => a.eval(valuation) | b.eval(valuation),
+            Self::Xor(a, b) => a.eval(valuation) ^ b.eval(valuation),
+            Self::Implies(a, b) => a.eval(valuation).implies(b.eval(valuation)),
+            Self::Iff(a, b) => a.eval(valuation).equiv(b.eval(valuation))
+        }
+    }
+
+    /// Returns the value of the expression under a valuation, along with the unknown
+    /// variables that kept it from being definite
+    ///
+    /// A variable only blocks the result when it is unknown and the rest of the expression
+    /// does not already decide the value without it.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr = (Expr::var(0) & Expr::var(1)) | Expr::var(2);
+    /// let evaluation = expr.explain(&[TriState::False, TriState::Default, TriState::Default][..]);
+    ///
+    /// assert_eq!(evaluation.value, TriState::Default);
+    /// assert!(evaluation.blocking.into_iter().eq([&2]));
+    /// assert!(expr.explain(&[TriState::True, TriState::True][..]).is_definite());
+    /// ```
+    pub fn explain<V: Valuation<K> + ?Sized>(&self, valuation: &V) -> Evaluation<'_, K>
+    where
+        K: Ord
+    {
+        let mut blocking = BTreeSet::new();
+        let value = self.explain_into(valuation, &mut blocking);
+        Evaluation { value, blocking }
+    }
+
+    /// Evaluates the expression, adding the variables blocking an unknown result to `blocking`
+    fn explain_into<'a, V: Valuation<K> + ?Sized>(&'a self, valuation: &V, blocking: &mut BTreeSet<&'a K>) -> TriState
+    where
+        K: Ord
+    {
+        let (a, b, value) = match self {
+            Self::Const(value) => return *value,
+            Self::Var(key) => {
+                let value = valuation.value(key);
+                if value == TriState::Default {
+                    blocking.insert(key);
+                }
+                return value;
+            }
+            Self::Not(inner) => return !inner.explain_into(valuation, blocking),
+            Self::And(a, b) => (a, b, a.eval(valuation) & b.eval(valuation)),
+            Self::Or(a, b) => (a, b, a.eval(valuation) | b.eval(valuation)),
+            Self::Xor(a, b) => (a, b, a.eval(valuation) ^ b.eval(valuation)),
+            Self::Implies(a, b) => (a, b, a.eval(valuation).implies(b.eval(valuation))),
+            Self::Iff(a, b) => (a, b, a.eval(valuation).equiv(b.eval(valuation)))
+        };
+        // A definite operand cannot block an unknown result, and a definite result has no
+        // blocking variables at all
+        if value == TriState::Default {
+            for operand in [a, b] {
+                if operand.eval(valuation) == TriState::Default {
+                    operand.explain_into(valuation, blocking);
+                }
+            }
+        }
+        value
+    }
+
+    /// Returns the variables that appear in the expression
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr = Expr::var('b').implies(Expr::var('a') ^ Expr::var('b'));
+    /// assert!(expr.free_vars().into_iter().eq([&'a', &'b']));
+    /// ```
+    pub fn free_vars(&self) -> BTreeSet<&K>
+    where
+        K: Ord
+    {
+        let mut vars = BTreeSet::new();
+        self.visit_vars(&mut |key| {
+            vars.insert(key);
+        });
+        vars
+    }
+
+    fn visit_vars<'a, F: FnMut(&'a K)>(&'a self, f: &mut F) {
+        match self {
+            Self::Const(_) => {}
+            Self::Var(key) => f(key),
+            Self::Not(inner) => inner.visit_vars(f),
+            Self::And(a, b) | Self::Or(a, b) | Self::Xor(a, b) | Self::Implies(a, b) | Self::Iff(a, b) => {
+                a.visit_vars(f);
+                b.visit_vars(f);
+            }
+        }
+    }
+
+    /// Returns the expression with every variable for which `f` returns an expression replaced
+    /// by that expression
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr = Expr::var("a") & Expr::var("b");
+    /// let substituted = expr.substitute(|&key| match key {
+    ///     "a" => Some(Expr::var("c") | Expr::var("d")),
+    ///     "b" => Some(Expr::Const(TriState::True)),
+    ///     _ => None
+    /// });
+    /// assert_eq!(substituted.to_string(), "(c || d) && true");
+    /// ```
+    pub fn substitute<F: FnMut(&K) -> Option<Expr<K>>>(&self, mut f: F) -> Expr<K>
+    where
+        K: Clone
+    {
+        self.substitute_with(&mut f)
+    }
+
+    fn substitute_with<F: FnMut(&K) -> Option<Expr<K>>>(&self, f: &mut F) -> Expr<K>
+    where
+        K: Clone
+    {
+        match self {
+            Self::Const(value) => Self::Const(*value),
+            Self::Var(key) => f(key).unwrap_or_else(|| Self::Var(key.clone())),
+            Self::Not(inner) => Self::Not(Box::new(inner.substitute_with(f))),
+            Self::And(a, b) => Self::And(Box::new(a.substitute_with(f)), Box::new(b.substitute_with(f))),
+            Self::Or(a, b) => Self::Or(Box::new(a.substitute_with(f)), Box::new(b.substitute_with(f))),
+            Self::Xor(a, b) => Self::Xor(Box::new(a.substitute_with(f)), Box::new(b.substitute_with(f))),
+            Self::Implies(a, b) => Self::Implies(Box::new(a.substitute_with(f)), Box::new(b.substitute_with(f))),
+            Self::Iff(a, b) => Self::Iff(Box::new(a.substitute_with(f)), Box::new(b.substitute_with(f)))
+        }
+    }
+
+    /// Returns the expression with every variable key mapped by `f`
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr = Expr::var(0) | !Expr::var(1);
+    /// assert_eq!(expr.map_vars(|index| format!("x{}", index)).to_string(), "x0 || !x1");
+    /// ```
+    pub fn map_vars<L, F: FnMut(K) -> L>(self, mut f: F) -> Expr<L> {
+        self.map_vars_with(&mut f)
+    }
+
+    fn map_vars_with<L, F: FnMut(K) -> L>(self, f: &mut F) -> Expr<L> {
+        match self {
+            Self::Const(value) => Expr::Const(value),
+            Self::Var(key) => Expr::Var(f(key)),
+            Self::Not(inner) => Expr::Not(Box::new(inner.map_vars_with(f))),
+            Self::And(a, b) => Expr::And(Box::new(a.map_vars_with(f)), Box::new(b.map_vars_with(f))),
+            Self::Or(a, b) => Expr::Or(Box::new(a.map_vars_with(f)), Box::new(b.map_vars_with(f))),
+            Self::Xor(a, b) => Expr::Xor(Box::new(a.map_vars_with(f)), Box::new(b.map_vars_with(f))),
+            Self::Implies(a, b) => Expr::Implies(Box::new(a.map_vars_with(f)), Box::new(b.map_vars_with(f))),
+            Self::Iff(a, b) => Expr::Iff(Box::new(a.map_vars_with(f)), Box::new(b.map_vars_with(f)))
+        }
+    }
+
+    /// Returns the binding strength of the operator at the root, where higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Const(_) | Self::Var(_) | Self::Not(_) => 6,
+            Self::And(..) => 5,
+            Self::Or(..) => 4,
+            Self::Xor(..) => 3,
+            Self::Implies(..) => 2,
+            Self::Iff(..) => 1
+        }
+    }
+}
+
+impl<K> From<TriState> for Expr<K> {
+    fn from(value: TriState) -> Self {
+        Self::Const(value)
+    }
+}
+
+impl<K> From<bool> for Expr<K> {
+    fn from(value: bool) -> Self {
+        Self::Const(TriState::from(value))
+    }
+}
+
+impl<K> Not for Expr<K> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+}
+
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $variant:ident) => {
+        impl<K> $op for Expr<K> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self::$variant(Box::new(self), Box::new(rhs))
+            }
+        }
+    };
+}
+
+binary_op!(BitAnd, bitand, And);
+binary_op!(BitOr, bitor, Or);
+binary_op!(BitXor, bitxor, Xor);
+
+impl<K: Display> Display for Expr<K> {
+    /// Formats the expression with the operators `!`, `&&`, `||`, `^`, `->` and `<->`, from
+    /// tightest to loosest binding, and only the parentheses needed to keep its structure
+    ///
+    /// The binary operators group to the left, except for `->` which groups to the right.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::Expr;
+    ///
+    /// let (a, b, c) = (Expr::var('a'), Expr::var('b'), Expr::var('c'));
+    /// assert_eq!(a.clone().implies(b.clone()).implies(c.clone()).to_string(), "(a -> b) -> c");
+    /// assert_eq!(a.clone().implies(b.clone().implies(c.clone())).to_string(), "a -> b -> c");
+    /// assert_eq!((a.clone() ^ (b.clone() ^ c)).to_string(), "a ^ (b ^ c)");
+    /// assert_eq!((!(a & b) | Expr::from(TriState::Default)).to_string(), "!(a && b) || unknown");
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let (a, b, symbol) = match self {
+            Self::Const(TriState::True) => return f.write_str("true"),
+            Self::Const(TriState::False) => return f.write_str("false"),
+            Self::Const(TriState::Default) => return f.write_str("unknown"),
+            Self::Var(key) => return Display::fmt(key, f),
+            Self::Not(inner) => {
+                f.write_str("!")?;
+                return write_operand(f, inner, inner.precedence() < self.precedence());
+            }
+            Self::And(a, b) => (a, b, "&&"),
+            Self::Or(a, b) => (a, b, "||"),
+            Self::Xor(a, b) => (a, b, "^"),
+            Self::Implies(a, b) => (a, b, "->"),
+            Self::Iff(a, b) => (a, b, "<->")
+        };
+        let right_associative = matches!(self, Self::Implies(..));
+        let precedence = self.precedence();
+        write_operand(f, a, a.precedence() < precedence || (right_associative && a.precedence() == precedence))?;
+        write!(f, " {} ", symbol)?;
+        write_operand(f, b, b.precedence() < precedence || (!right_associative && b.precedence() == precedence))
+    }
+}
+
+fn write_operand<K: Display>(f: &mut Formatter<'_>, operand: &Expr<K>, parenthesize: bool) -> Result<(), core::fmt::Error> {
+    if parenthesize { write!(f, "({})", operand) } else { Display::fmt(operand, f) }
+}
+
+/// The result of [`Expr::explain`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Evaluation<'a, K> {
+    /// The value of the expression
+    pub value: TriState,
+    /// The unknown variables that kept the value from being definite, which is empty when the
+    /// value is definite
+    pub blocking: BTreeSet<&'a K>
+}
+
+impl<K> Evaluation<'_, K> {
+    /// Returns true if the expression evaluated to a definite value
+    pub fn is_definite(&self) -> bool {
+        self.value != TriState::Default
+    }
+}
+
+/// A source of values for the variables of an [`Expr`]
+pub trait Valuation<K> {
+    /// Returns the value of a variable, which is `TriState::Default` when it is unknown
+    fn value(&self, key: &K) -> TriState;
+}
+
+impl<K, F: Fn(&K) -> TriState> Valuation<K> for F {
+    fn value(&self, key: &K) -> TriState {
+        self(key)
+    }
+}
+
+impl<K: Ord> Valuation<K> for BTreeMap<K, TriState> {
+    fn value(&self, key: &K) -> TriState {
+        self.get(key).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + core::hash::Hash, S: core::hash::BuildHasher> Valuation<K> for std::collections::HashMap<K, TriState, S> {
+    fn value(&self, key: &K) -> TriState {
+        self.get(key).copied().unwrap_or_default()
+    }
+}
+
+impl Valuation<usize> for [TriState] {
+    /// Returns the value at the index of the variable, where variables beyond the end are
+    /// unknown
+    fn value(&self, key: &usize) -> TriState {
+        self.get(*key).copied().unwrap_or_default()
+    }
+}
+
+impl Valuation<usize> for Vec<TriState> {
+    fn value(&self, key: &usize) -> TriState {
+        self[..].value(key)
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::error::Error;
+use core::fmt::{Display, Formatter, Write};
+use core::ops::Range;
+use core::str::FromStr;
+
+use super::Expr;
+use crate::TriState;
+
+/// The deepest nesting of parentheses, negations and right grouped operators the parser accepts,
+/// which keeps it from running out of stack on hostile input
+const MAX_DEPTH: usize = 256;
+
+impl FromStr for Expr<String> {
+    type Err = ParseExprError;
+
+    /// Parses an expression written with the operators `!`, `&&`, `||`, `^`, `->` and `<->`,
+    /// from tightest to loosest binding, and parentheses
+    ///
+    /// Variables are identifiers made of letters, digits, `_` and `.` that do not start with a
+    /// digit or `.`. The words `true` and `false` are constants, and `unknown` and `default`
+    /// both stand for the unknown constant. Expressions nested more than 256 levels deep are
+    /// rejected.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::{Expr, ParseExprErrorKind};
+    ///
+    /// let expr: Expr<String> = "a && (b || !c) -> d".parse().unwrap();
+    /// assert_eq!(expr, (Expr::var("a") & (Expr::var("b") | !Expr::var("c"))).implies(Expr::var("d")).map_vars(String::from));
+    ///
+    /// let expr: Expr<String> = "x <-> default".parse().unwrap();
+    /// assert_eq!(expr, Expr::var("x".to_owned()).iff(Expr::Const(TriState::Default)));
+    ///
+    /// let nested = format!("{}a{}", "(".repeat(256), ")".repeat(256));
+    /// assert!(nested.parse::<Expr<String>>().is_ok());
+    /// for deep in ["(".repeat(100_000), "!".repeat(100_000), "a -> ".repeat(100_000) + "a"] {
+    ///     assert_eq!(deep.parse::<Expr<String>>().unwrap_err().kind(), ParseExprErrorKind::TooDeep);
+    /// }
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input: s, position: 0, depth: 0 };
+        let expr = parser.expression(1)?;
+        match parser.next()? {
+            (Token::End, _) => Ok(expr),
+            (_, span) => Err(parser.error(ParseExprErrorKind::ExpectedOperator, span))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Token<'a> {
+    Not,
+    And,
+    Or,
+    Xor,
+    Implies,
+    Iff,
+    Open,
+    Close,
+    Word(&'a str),
+    End
+}
+
+impl Token<'_> {
+    /// Returns the binding strength of a binary operator and whether it groups to the right
+    fn binary(self) -> Option<(u8, bool)> {
+        match self {
+            Token::And => Some((5, false)),
+            Token::Or => Some((4, false)),
+            Token::Xor => Some((3, false)),
+            Token::Implies => Some((2, true)),
+            Token::Iff => Some((1, false)),
+            _ => None
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    depth: usize
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the next token and its span without consuming it
+    fn peek(&self) -> Result<(Token<'a>, Range<usize>), ParseExprError> {
+        let rest = &self.input[self.position..];
+        let start = self.position + (rest.len() - rest.trim_start().len());
+        let rest = &self.input[start..];
+
+        let symbols = [
+            ("<->", Token::Iff),
+            ("->", Token::Implies),
+            ("&&", Token::And),
+            ("||", Token::Or),
+            ("^", Token::Xor),
+            ("!", Token::Not),
+            ("(", Token::Open),
+            (")", Token::Close)
+        ];
+        if let Some(&(symbol, token)) = symbols.iter().find(|(symbol, _)| rest.starts_with(symbol)) {
+            return Ok((token, start..start + symbol.len()));
+        }
+
+        match rest.chars().next() {
+            None => Ok((Token::End, start..start)),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let len = rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+                Ok((Token::Word(&rest[..len]), start..start + len))
+            }
+            Some(c) => Err(self.error(ParseExprErrorKind::InvalidCharacter(c), start..start + c.len_utf8()))
+        }
+    }
+
+    /// Returns the next token and its span
+    fn next(&mut self) -> Result<(Token<'a>, Range<usize>), ParseExprError> {
+        let (token, span) = self.peek()?;
+        self.position = span.end;
+        Ok((token, span))
+    }
+
+    /// Parses an expression whose binary operators all bind at least as tightly as `min`
+    fn expression(&mut self, min: u8) -> Result<Expr<String>, ParseExprError> {
+        let mut lhs = self.operand()?;
+        while let Some((precedence, right)) = self.peek()?.0.binary() {
+            if precedence < min {
+                break;
+            }
+            let (token, span) = self.next()?;
+            let rhs = self.nested(span, |parser| parser.expression(if right { precedence } else { precedence + 1 }))?;
+            let (a, b) = (Box::new(lhs), Box::new(rhs));
+            lhs = match token {
+                Token::And => Expr::And(a, b),
+                Token::Or => Expr::Or(a, b),
+                Token::Xor => Expr::Xor(a, b),
+                Token::Implies => Expr::Implies(a, b),
+                _ => Expr::Iff(a, b)
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<Expr<String>, ParseExprError> {
+        match self.next()? {
+            (Token::Not, span) => Ok(Expr::Not(Box::new(self.nested(span, Self::operand)?))),
+            (Token::Open, open) => {
+                let expr = self.nested(open.clone(), |parser| parser.expression(1))?;
+                match self.next()? {
+                    (Token::Close, _) => Ok(expr),
+                    (Token::End, _) => Err(self.error(ParseExprErrorKind::UnclosedParenthesis, open)),
+                    (_, span) => Err(self.error(ParseExprErrorKind::ExpectedOperator, span))
+                }
+            }
+            (Token::Word("true"), _) => Ok(Expr::Const(TriState::True)),
+            (Token::Word("false"), _) => Ok(Expr::Const(TriState::False)),
+            (Token::Word("unknown"), _) | (Token::Word("default"), _) => Ok(Expr::Const(TriState::Default)),
+            (Token::Word(word), _) => Ok(Expr::Var(word.to_string())),
+            (_, span) => Err(self.error(ParseExprErrorKind::ExpectedOperand, span))
+        }
+    }
+
+    /// Parses with `f` one level deeper, failing at the token `span` if that is too deep
+    fn nested<T>(&mut self, span: Range<usize>, f: impl FnOnce(&mut Self) -> Result<T, ParseExprError>) -> Result<T, ParseExprError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ParseExprErrorKind::TooDeep, span));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn error(&self, kind: ParseExprErrorKind, span: Range<usize>) -> ParseExprError {
+        ParseExprError { input: self.input.to_string(), kind, span }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// The error returned when parsing an [`Expr`] fails
+///
+/// ```rust
+/// use tristate::expr::{Expr, ParseExprErrorKind};
+///
+/// let error = "a && (b || )".parse::<Expr<String>>().unwrap_err();
+/// assert_eq!(error.kind(), ParseExprErrorKind::ExpectedOperand);
+/// assert_eq!(error.span(), 11..12);
+/// assert_eq!(error.to_string(), "expected a variable, constant, `!` or `(` at 11..12");
+/// assert_eq!(error.diagnostic(), "expected a variable, constant, `!` or `(` at 11..12\na && (b || )\n           ^");
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseExprError {
+    input: String,
+    kind: ParseExprErrorKind,
+    span: Range<usize>
+}
+
+impl ParseExprError {
+    /// Returns the kind of error
+    pub fn kind(&self) -> ParseExprErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte range of the input at fault, which is empty at the end of the input
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the text that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the error message followed by the line of input at fault, with carets under
+    /// the span
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let error = "first &&\n(second | third)".parse::<Expr<String>>().unwrap_err();
+    /// assert_eq!(error.diagnostic(), "invalid character '|' at 17..18\n(second | third)\n        ^");
+    /// ```
+    pub fn diagnostic(&self) -> String {
+        let line_start = self.input[..self.span.start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = self.input[self.span.start..].find('\n').map_or(self.input.len(), |index| self.span.start + index);
+        let line = &self.input[line_start..line_end];
+        let column = self.input[line_start..self.span.start].chars().count();
+        let width = self.input[self.span.start..self.span.end.min(line_end)].chars().count().max(1);
+
+        let mut diagnostic = String::new();
+        // Writing to a string cannot fail
+        let _ = write!(diagnostic, "{}\n{}\n{:column$}{:^<width$}", self, line, "", "", column = column, width = width);
+        diagnostic
+    }
+}
+
+impl Display for ParseExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl Error for ParseExprError {}
+
+/// The kind of [`ParseExprError`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ParseExprErrorKind {
+    /// A character that does not start any token
+    InvalidCharacter(char),
+    /// A variable, constant, `!` or `(` was expected
+    ExpectedOperand,
+    /// A binary operator, `)` or the end of input was expected
+    ExpectedOperator,
+    /// An opening parenthesis is never closed
+    UnclosedParenthesis,
+    /// The expression is nested too deeply
+    TooDeep
+}
+
+impl Display for ParseExprErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Self::ExpectedOperand => f.write_str("expected a variable, constant, `!` or `(`"),
+            Self::ExpectedOperator => f.write_str("expected an operator or the end of the expression"),
+            Self::UnclosedParenthesis => f.write_str("unclosed parenthesis"),
+            Self::TooDeep => f.write_str("expression nested too deeply")
+        }
+    }
+}
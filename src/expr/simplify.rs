@@ -0,0 +1,284 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::Expr;
+use crate::TriState;
+
+impl<K: Clone + PartialEq> Expr<K> {
+    /// Returns an equivalent expression with constants folded and redundant structure removed
+    ///
+    /// Only rewrites that hold in strong Kleene logic are applied: constant folding, double
+    /// negation, idempotence and absorption. Rewrites that rely on the law of excluded middle,
+    /// such as `a || !a = true` or `a ^ a = false`, do not hold when `a` is unknown and are
+    /// left alone.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let simplify = |text: &str| text.parse::<Expr<String>>().unwrap().simplify().to_string();
+    ///
+    /// assert_eq!(simplify("a && true && !!b"), "a && b");
+    /// assert_eq!(simplify("(a || false) && (a || b)"), "a");
+    /// assert_eq!(simplify("unknown && false || c -> true"), "true");
+    /// assert_eq!(simplify("a ^ unknown"), "unknown");
+    /// assert_eq!(simplify("a || !a"), "a || !a");
+    /// assert_eq!(simplify("a <-> a"), "a <-> a");
+    /// ```
+    pub fn simplify(&self) -> Expr<K> {
+        match self {
+            Self::Const(value) => Self::Const(*value),
+            Self::Var(key) => Self::Var(key.clone()),
+            Self::Not(inner) => negate(inner.simplify()),
+            Self::And(a, b) => and(a.simplify(), b.simplify()),
+            Self::Or(a, b) => or(a.simplify(), b.simplify()),
+            Self::Xor(a, b) => match (a.simplify(), b.simplify()) {
+                (Self::Const(TriState::Default), _) | (_, Self::Const(TriState::Default)) => Self::Const(TriState::Default),
+                (Self::Const(TriState::False), other) | (other, Self::Const(TriState::False)) => other,
+                (Self::Const(TriState::True), other) | (other, Self::Const(TriState::True)) => negate(other),
+                (a, b) => a ^ b
+            },
+            Self::Implies(a, b) => match (a.simplify(), b.simplify()) {
+                (Self::Const(TriState::False), _) | (_, Self::Const(TriState::True)) => Self::Const(TriState::True),
+                (Self::Const(TriState::True), b) => b,
+                (a, Self::Const(TriState::False)) => negate(a),
+                (a, b) => a.implies(b)
+            },
+            Self::Iff(a, b) => match (a.simplify(), b.simplify()) {
+                (Self::Const(TriState::Default), _) | (_, Self::Const(TriState::Default)) => Self::Const(TriState::Default),
+                (Self::Const(TriState::True), other) | (other, Self::Const(TriState::True)) => other,
+                (Self::Const(TriState::False), other) | (other, Self::Const(TriState::False)) => negate(other),
+                (a, b) => a.iff(b)
+            }
+        }
+    }
+
+    /// Returns an equivalent expression in negation normal form, built only from `&&`, `||`,
+    /// constants and variables that are negated at most once
+    ///
+    /// `a -> b` becomes `!a || b`, `a <-> b` becomes `(!a || b) && (!b || a)` and `a ^ b`
+    /// becomes `(a || b) && (!a || !b)`, which agree with the original connectives on unknown
+    /// values as well.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr: Expr<String> = "!(a -> (b ^ c))".parse().unwrap();
+    /// assert_eq!(expr.nnf().to_string(), "a && (!b && !c || b && c)");
+    /// ```
+    pub fn nnf(&self) -> Expr<K> {
+        self.nnf_with(false).simplify()
+    }
+
+    fn nnf_with(&self, negated: bool) -> Expr<K> {
+        let connective = |a: Expr<K>, b: Expr<K>| if negated { a | b } else { a & b };
+        let dual = |a: Expr<K>, b: Expr<K>| if negated { a & b } else { a | b };
+        match self {
+            Self::Const(value) => Self::Const(if negated { !*value } else { *value }),
+            Self::Var(key) => if negated { !Self::Var(key.clone()) } else { Self::Var(key.clone()) },
+            Self::Not(inner) => inner.nnf_with(!negated),
+            Self::And(a, b) => connective(a.nnf_with(negated), b.nnf_with(negated)),
+            Self::Or(a, b) => dual(a.nnf_with(negated), b.nnf_with(negated)),
+            Self::Implies(a, b) => dual(a.nnf_with(!negated), b.nnf_with(negated)),
+            Self::Iff(a, b) => connective(
+                dual(a.nnf_with(!negated), b.nnf_with(negated)),
+                dual(b.nnf_with(!negated), a.nnf_with(negated))
+            ),
+            Self::Xor(a, b) => connective(
+                dual(a.nnf_with(negated), b.nnf_with(negated)),
+                dual(a.nnf_with(!negated), b.nnf_with(!negated))
+            )
+        }
+    }
+
+    /// Returns an equivalent expression in conjunctive normal form, a conjunction of
+    /// disjunctions of possibly negated variables and constants
+    ///
+    /// The result can be exponentially larger than the expression. Clauses such as `a || !a`
+    /// are kept, as they are not true when `a` is unknown.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr: Expr<String> = "(a && b) || (c && a)".parse().unwrap();
+    /// assert_eq!(expr.cnf().to_string(), "a && (b || c)");
+    /// assert_eq!("a ^ b".parse::<Expr<String>>().unwrap().cnf().to_string(), "(a || b) && (!a || !b)");
+    /// ```
+    pub fn cnf(&self) -> Expr<K> {
+        normal_form(&self.nnf(), true)
+    }
+
+    /// Returns an equivalent expression in disjunctive normal form, a disjunction of
+    /// conjunctions of possibly negated variables and constants
+    ///
+    /// The result can be exponentially larger than the expression. Terms such as `a && !a`
+    /// are kept, as they are not false when `a` is unknown.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let expr: Expr<String> = "a ^ b".parse().unwrap();
+    /// assert_eq!(expr.dnf().to_string(), "a && !a || a && !b || b && !a || b && !b");
+    /// ```
+    pub fn dnf(&self) -> Expr<K> {
+        normal_form(&self.nnf(), false)
+    }
+}
+
+impl<K: Ord> Expr<K> {
+    /// Returns true if both expressions have the same value under every assignment of their
+    /// variables, including assignments that leave some of them unknown
+    ///
+    /// This checks all `3^n` assignments of the `n` variables, so it is only practical for
+    /// small expressions.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    ///
+    /// let parse = |text: &str| text.parse::<Expr<String>>().unwrap();
+    ///
+    /// assert!(parse("!(a && b)").equivalent(&parse("!a || !b")));
+    /// assert!(parse("a -> b").equivalent(&parse("!b -> !a")));
+    /// assert!(!parse("a || !a").equivalent(&parse("true")));
+    /// ```
+    pub fn equivalent(&self, other: &Expr<K>) -> bool {
+        self.counterexample(other).is_none()
+    }
+
+    /// Returns an assignment of the variables of both expressions under which their values
+    /// differ, or `None` if they are equivalent
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::Expr;
+    ///
+    /// let a = "a || !a".parse::<Expr<String>>().unwrap();
+    /// let counterexample = a.counterexample(&Expr::Const(TriState::True)).unwrap();
+    /// assert_eq!(counterexample[&"a".to_owned()], TriState::Default);
+    /// ```
+    pub fn counterexample<'a>(&'a self, other: &'a Expr<K>) -> Option<BTreeMap<&'a K, TriState>> {
+        let mut vars = self.free_vars();
+        vars.extend(other.free_vars());
+        let vars: Vec<&K> = vars.into_iter().collect();
+
+        let mut values = vec![TriState::False; vars.len()];
+        loop {
+            let valuation = |key: &K| vars.iter().position(|var| *var == key).map_or(TriState::Default, |index| values[index]);
+            if self.eval(&valuation) != other.eval(&valuation) {
+                return Some(vars.into_iter().zip(values).collect());
+            }
+            // Step to the next assignment, counting in base three
+            let mut index = 0;
+            loop {
+                match values.get_mut(index) {
+                    None => return None,
+                    Some(value @ TriState::False) => *value = TriState::Default,
+                    Some(value @ TriState::Default) => *value = TriState::True,
+                    Some(value @ TriState::True) => {
+                        *value = TriState::False;
+                        index += 1;
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn negate<K: Clone + PartialEq>(expr: Expr<K>) -> Expr<K> {
+    match expr {
+        Expr::Const(value) => Expr::Const(!value),
+        Expr::Not(inner) => *inner,
+        expr => !expr
+    }
+}
+
+fn and<K: Clone + PartialEq>(a: Expr<K>, b: Expr<K>) -> Expr<K> {
+    match (a, b) {
+        (Expr::Const(TriState::False), _) | (_, Expr::Const(TriState::False)) => Expr::Const(TriState::False),
+        (Expr::Const(TriState::True), other) | (other, Expr::Const(TriState::True)) => other,
+        (a, b) if a == b || absorbs(&a, &b, false) => a,
+        (a, b) if absorbs(&b, &a, false) => b,
+        (a, b) => a & b
+    }
+}
+
+fn or<K: Clone + PartialEq>(a: Expr<K>, b: Expr<K>) -> Expr<K> {
+    match (a, b) {
+        (Expr::Const(TriState::True), _) | (_, Expr::Const(TriState::True)) => Expr::Const(TriState::True),
+        (Expr::Const(TriState::False), other) | (other, Expr::Const(TriState::False)) => other,
+        (a, b) if a == b || absorbs(&a, &b, true) => a,
+        (a, b) if absorbs(&b, &a, true) => b,
+        (a, b) => a | b
+    }
+}
+
+/// Returns true if `a` absorbs `b`, as `a` does in `a && (a || c)` and `a || (a && c)`
+fn absorbs<K: PartialEq>(a: &Expr<K>, b: &Expr<K>, disjunction: bool) -> bool {
+    match (b, disjunction) {
+        (Expr::Or(x, y), false) | (Expr::And(x, y), true) => **x == *a || **y == *a,
+        _ => false
+    }
+}
+
+/// Returns the conjunctive or disjunctive normal form of an expression in negation normal form
+fn normal_form<K: Clone + PartialEq>(nnf: &Expr<K>, conjunctive: bool) -> Expr<K> {
+    // A group holding the first constant is decided and drops out, while the second constant
+    // drops out of any group holding it
+    let (decided, neutral) = if conjunctive { (TriState::True, TriState::False) } else { (TriState::False, TriState::True) };
+
+    let mut groups: Vec<Vec<Expr<K>>> = Vec::new();
+    for group in groups_of(nnf, conjunctive) {
+        let mut literals: Vec<Expr<K>> = Vec::new();
+        for literal in group {
+            if !literals.contains(&literal) {
+                literals.push(literal);
+            }
+        }
+        if literals.contains(&Expr::Const(decided)) {
+            continue;
+        }
+        literals.retain(|literal| *literal != Expr::Const(neutral));
+        groups.push(literals);
+    }
+
+    // Absorption removes every group that contains all the literals of another
+    let mut kept: Vec<Vec<Expr<K>>> = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        let absorbed = groups.iter().enumerate().any(|(other_index, other)| {
+            other_index != index
+                && other.iter().all(|literal| group.contains(literal))
+                && (other.len() < group.len() || other_index < index)
+        });
+        if !absorbed {
+            kept.push(group.clone());
+        }
+    }
+
+    let combine = |a: Expr<K>, b: Expr<K>, outer: bool| if outer == conjunctive { a & b } else { a | b };
+    kept.into_iter()
+        .map(|group| group.into_iter().reduce(|a, b| combine(a, b, false)).unwrap_or(Expr::Const(neutral)))
+        .reduce(|a, b| combine(a, b, true))
+        .unwrap_or(Expr::Const(decided))
+}
+
+/// Returns the groups of literals of an expression in negation normal form, where the groups
+/// are joined by `&&` and the literals within them by `||` when `conjunctive`, and the other
+/// way around otherwise
+fn groups_of<K: Clone>(nnf: &Expr<K>, conjunctive: bool) -> Vec<Vec<Expr<K>>> {
+    match (nnf, conjunctive) {
+        (Expr::And(a, b), true) | (Expr::Or(a, b), false) => {
+            let mut groups = groups_of(a, conjunctive);
+            groups.extend(groups_of(b, conjunctive));
+            groups
+        }
+        (Expr::Or(a, b), true) | (Expr::And(a, b), false) => {
+            let (a, b) = (groups_of(a, conjunctive), groups_of(b, conjunctive));
+            a.iter()
+                .flat_map(|x| b.iter().map(move |y| x.iter().chain(y).cloned().collect()))
+                .collect()
+        }
+        (literal, _) => vec![vec![literal.clone()]]
+    }
+}
@@ -0,0 +1,385 @@
+//! An in-process registry of named feature flags.
+//!
+//! Each [`Flag`] is a handle with a name and a compiled-in default, usually declared as a static
+//! with [`declare_flags!`](crate::declare_flags). A [`FlagRegistry`] holds a `TriState` override
+//! for every registered flag, which operators can change at runtime by handle or by name. A flag
+//! is enabled when its override is `TriState::True`, disabled when it is `TriState::False`, and
+//! follows its default otherwise.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::flags::FlagRegistry;
+//!
+//! tristate::declare_flags! {
+//!     /// Serves pages with the redesigned layout
+//!     pub static NEW_LAYOUT = ("new-layout", false);
+//!     /// Caches rendered pages
+//!     static PAGE_CACHE = ("page-cache", true);
+//! }
+//!
+//! static FLAGS: FlagRegistry = FlagRegistry::new();
+//!
+//! FLAGS.register(&NEW_LAYOUT);
+//! FLAGS.register(&PAGE_CACHE);
+//! assert!(!FLAGS.is_enabled(&NEW_LAYOUT));
+//!
+//! FLAGS.set_named("new-layout", "on".parse().unwrap()).unwrap();
+//! FLAGS.set(&PAGE_CACHE, TriState::False);
+//! assert!(FLAGS.is_enabled(&NEW_LAYOUT));
+//! assert!(!FLAGS.is_enabled(&PAGE_CACHE));
+//!
+//! FLAGS.set(&PAGE_CACHE, TriState::Default);
+//! assert!(FLAGS.is_enabled(&PAGE_CACHE));
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::string::String;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use std::vec::Vec;
+
+use crate::TriState;
+
+/// A handle to a named feature flag with a compiled-in default
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Flag {
+    name: &'static str,
+    default: bool
+}
+
+impl Flag {
+    /// Returns a handle to the flag with the given name and default
+    pub const fn new(name: &'static str, default: bool) -> Self {
+        Self { name, default }
+    }
+
+    /// Returns the name of the flag
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the value of the flag when it is not overridden
+    pub const fn default(&self) -> bool {
+        self.default
+    }
+}
+
+/// Declares feature flags as statics holding a [`Flag`]
+///
+/// Each declaration gives the name and default of a flag, and may carry attributes and a
+/// visibility.
+///
+/// ```rust
+/// tristate::declare_flags! {
+///     /// Enables verbose logging
+///     pub static VERBOSE = ("verbose", false);
+/// }
+///
+/// assert_eq!(VERBOSE.name(), "verbose");
+/// assert!(!VERBOSE.default());
+/// ```
+#[macro_export]
+macro_rules! declare_flags {
+    ($($(#[$attr:meta])* $vis:vis static $ident:ident = ($name:expr, $default:expr);)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $ident: $crate::flags::Flag = $crate::flags::Flag::new($name, $default);
+        )*
+    };
+}
+
+/// A change to the override of a flag, as passed to the hooks of a [`FlagRegistry`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FlagChange {
+    /// The flag that changed
+    pub flag: Flag,
+    /// The override before the change
+    pub previous: TriState,
+    /// The override after the change
+    pub current: TriState
+}
+
+impl FlagChange {
+    /// Returns whether the flag was enabled before the change
+    pub fn was_enabled(&self) -> bool {
+        self.previous.or_else(self.flag.default)
+    }
+
+    /// Returns whether the flag is enabled after the change
+    pub fn is_enabled(&self) -> bool {
+        self.current.or_else(self.flag.default)
+    }
+}
+
+type Hook = Arc<dyn Fn(&FlagChange) + Send + Sync>;
+
+/// A registry of feature flags and their runtime overrides
+///
+/// The registry can be shared between threads and held in a static. Hooks added with
+/// [`on_change`](Self::on_change) are called after every change to an override, outside of the
+/// locks of the registry, so they are free to read and change it and to add further hooks.
+pub struct FlagRegistry {
+    flags: Mutex<BTreeMap<&'static str, (Flag, TriState)>>,
+    hooks: RwLock<Vec<Hook>>
+}
+
+impl FlagRegistry {
+    /// Returns a registry without any flags
+    pub const fn new() -> Self {
+        Self { flags: Mutex::new(BTreeMap::new()), hooks: RwLock::new(Vec::new()) }
+    }
+
+    /// Adds a flag to the registry so that it can be set by name, leaving the override of a
+    /// flag that is already registered unchanged
+    pub fn register(&self, flag: &Flag) {
+        self.lock().entry(flag.name).or_insert((*flag, TriState::Default));
+    }
+
+    /// Returns the registered flag with the given name
+    pub fn flag(&self, name: &str) -> Option<Flag> {
+        self.lock().get(name).map(|&(flag, _)| flag)
+    }
+
+    /// Returns the registered flags, ordered by name
+    pub fn flags(&self) -> Vec<Flag> {
+        self.lock().values().map(|&(flag, _)| flag).collect()
+    }
+
+    /// Returns the override of a flag, which is `TriState::Default` for an unregistered flag
+    pub fn get(&self, flag: &Flag) -> TriState {
+        self.lock().get(flag.name).map_or(TriState::Default, |&(_, state)| state)
+    }
+
+    /// Returns whether a flag is enabled, resolving its override against its default
+    pub fn is_enabled(&self, flag: &Flag) -> bool {
+        self.get(flag).or_else(flag.default)
+    }
+
+    /// Sets the override of a flag, registering it if needed, and returns the previous override
+    pub fn set(&self, flag: &Flag, state: TriState) -> TriState {
+        let previous = {
+            let mut flags = self.lock();
+            let entry = flags.entry(flag.name).or_insert((*flag, TriState::Default));
+            core::mem::replace(&mut entry.1, state)
+        };
+        self.notify(&[FlagChange { flag: *flag, previous, current: state }]);
+        previous
+    }
+
+    /// Sets the override of the registered flag with the given name and returns the previous
+    /// override
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::flags::FlagRegistry;
+    ///
+    /// let registry = FlagRegistry::new();
+    /// let error = registry.set_named("missing", TriState::True).unwrap_err();
+    /// assert_eq!(error.to_string(), "unknown feature flag \"missing\"");
+    /// ```
+    pub fn set_named(&self, name: &str, state: TriState) -> Result<TriState, UnknownFlagError> {
+        let flag = self.flag(name).ok_or_else(|| UnknownFlagError { name: name.into() })?;
+        Ok(self.set(&flag, state))
+    }
+
+    /// Overrides a flag until the returned guard is dropped, when the previous override is
+    /// restored
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::flags::{Flag, FlagRegistry};
+    ///
+    /// static FAST_PATH: Flag = Flag::new("fast-path", true);
+    /// let registry = FlagRegistry::new();
+    ///
+    /// {
+    ///     let _guard = registry.scoped(&FAST_PATH, TriState::False);
+    ///     assert!(!registry.is_enabled(&FAST_PATH));
+    /// }
+    /// assert!(registry.is_enabled(&FAST_PATH));
+    /// ```
+    #[must_use = "the override is reverted as soon as the guard is dropped"]
+    pub fn scoped(&self, flag: &Flag, state: TriState) -> ScopedOverride<'_> {
+        let previous = self.set(flag, state);
+        ScopedOverride { registry: self, flag: *flag, previous }
+    }
+
+    /// Returns the overrides of every registered flag
+    pub fn snapshot(&self) -> FlagSnapshot {
+        FlagSnapshot { flags: self.lock().clone() }
+    }
+
+    /// Restores the registry to a snapshot
+    ///
+    /// Flags registered since the snapshot stay registered, with their overrides reset to
+    /// `TriState::Default`.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::flags::{Flag, FlagRegistry};
+    ///
+    /// static A: Flag = Flag::new("a", false);
+    /// static B: Flag = Flag::new("b", false);
+    /// let registry = FlagRegistry::new();
+    ///
+    /// registry.set(&A, TriState::True);
+    /// let snapshot = registry.snapshot();
+    /// registry.set(&A, TriState::False);
+    /// registry.set(&B, TriState::True);
+    ///
+    /// registry.restore(&snapshot);
+    /// assert_eq!(registry.get(&A), TriState::True);
+    /// assert_eq!(registry.get(&B), TriState::Default);
+    /// ```
+    pub fn restore(&self, snapshot: &FlagSnapshot) {
+        let mut changes = Vec::new();
+        {
+            let mut flags = self.lock();
+            for (name, (flag, state)) in flags.iter_mut() {
+                let restored = snapshot.flags.get(name).map_or(TriState::Default, |&(_, state)| state);
+                if *state != restored {
+                    changes.push(FlagChange { flag: *flag, previous: *state, current: restored });
+                    *state = restored;
+                }
+            }
+            for (name, &(flag, state)) in &snapshot.flags {
+                flags.entry(name).or_insert_with(|| {
+                    changes.push(FlagChange { flag, previous: TriState::Default, current: state });
+                    (flag, state)
+                });
+            }
+        }
+        self.notify(&changes);
+    }
+
+    /// Adds a hook that is called with every change to the override of a flag
+    ///
+    /// Hooks are only called when an override actually changes, in the order they were added.
+    /// A hook added while others are running is first called for the next change.
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use tristate::TriState;
+    /// use tristate::flags::{Flag, FlagRegistry};
+    ///
+    /// static SEARCH: Flag = Flag::new("search", false);
+    /// let registry = FlagRegistry::new();
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// let sink = log.clone();
+    /// registry.on_change(move |change| {
+    ///     sink.lock().unwrap().push((change.flag.name(), change.was_enabled(), change.is_enabled()));
+    /// });
+    ///
+    /// registry.set(&SEARCH, TriState::True);
+    /// registry.set(&SEARCH, TriState::True);
+    /// drop(registry.scoped(&SEARCH, TriState::Default));
+    /// assert_eq!(*log.lock().unwrap(), [("search", false, true), ("search", true, false), ("search", false, true)]);
+    /// ```
+    ///
+    /// Hooks can change the registry themselves, such as to keep one flag in step with another.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::flags::{Flag, FlagRegistry};
+    ///
+    /// static SEARCH: Flag = Flag::new("search", false);
+    /// static SUGGESTIONS: Flag = Flag::new("suggestions", false);
+    /// static REGISTRY: FlagRegistry = FlagRegistry::new();
+    ///
+    /// REGISTRY.register(&SEARCH);
+    /// REGISTRY.register(&SUGGESTIONS);
+    /// REGISTRY.on_change(|change| {
+    ///     if change.flag == SEARCH {
+    ///         REGISTRY.set(&SUGGESTIONS, change.current);
+    ///         REGISTRY.on_change(|_| {});
+    ///     }
+    /// });
+    ///
+    /// REGISTRY.set(&SEARCH, TriState::True);
+    /// assert_eq!(REGISTRY.get(&SUGGESTIONS), TriState::True);
+    ///
+    /// drop(REGISTRY.scoped(&SEARCH, TriState::False));
+    /// assert_eq!(REGISTRY.get(&SUGGESTIONS), TriState::True);
+    /// ```
+    pub fn on_change<F: Fn(&FlagChange) + Send + Sync + 'static>(&self, hook: F) {
+        self.hooks.write().unwrap_or_else(PoisonError::into_inner).push(Arc::new(hook));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<&'static str, (Flag, TriState)>> {
+        // The map is never left half-updated, so a panic elsewhere does not invalidate it
+        self.flags.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify(&self, changes: &[FlagChange]) {
+        // Called on a copy of the list so that hooks can take the lock themselves
+        let hooks = self.hooks.read().unwrap_or_else(PoisonError::into_inner).clone();
+        for change in changes.iter().filter(|change| change.previous != change.current) {
+            for hook in hooks.iter() {
+                hook(change);
+            }
+        }
+    }
+}
+
+impl Default for FlagRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for FlagRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_map().entries(self.lock().iter().map(|(name, (_, state))| (name, state))).finish()
+    }
+}
+
+/// The overrides of the flags in a [`FlagRegistry`] at one point in time
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FlagSnapshot {
+    flags: BTreeMap<&'static str, (Flag, TriState)>
+}
+
+impl FlagSnapshot {
+    /// Returns the override of a flag at the time of the snapshot
+    pub fn get(&self, flag: &Flag) -> TriState {
+        self.flags.get(flag.name).map_or(TriState::Default, |&(_, state)| state)
+    }
+}
+
+/// A guard that restores the previous override of a flag when dropped, returned by
+/// [`FlagRegistry::scoped`]
+#[derive(Debug)]
+pub struct ScopedOverride<'a> {
+    registry: &'a FlagRegistry,
+    flag: Flag,
+    previous: TriState
+}
+
+impl Drop for ScopedOverride<'_> {
+    fn drop(&mut self) {
+        self.registry.set(&self.flag, self.previous);
+    }
+}
+
+/// The error returned when setting a flag by a name that is not registered
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct UnknownFlagError {
+    name: String
+}
+
+impl UnknownFlagError {
+    /// Returns the name that is not registered
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for UnknownFlagError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "unknown feature flag {:?}", self.name)
+    }
+}
+
+impl Error for UnknownFlagError {}
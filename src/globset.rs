@@ -0,0 +1,264 @@
+//! Integration with [`globset`](https://docs.rs/globset) for path rules in the syntax of
+//! `.gitignore` files, available with the `globset` feature.
+//!
+//! A [`RuleSet`] evaluates a path against ordered glob rules, of which the last to match
+//! decides: `TriState::True` for a rule such as `*.rs` and `TriState::False` for a negated rule
+//! such as `!main.rs`. A path no rule matches is `TriState::Default`.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::globset::RuleSet;
+//!
+//! let rules: RuleSet = "
+//! # Sources anywhere, except generated ones
+//! *.rs
+//! !*.generated.rs
+//!
+//! # Everything in the top level docs directory
+//! /docs/
+//! "
+//! .parse()
+//! .unwrap();
+//!
+//! assert_eq!(rules.evaluate("src/lib.rs", false), TriState::True);
+//! assert_eq!(rules.evaluate("src/parser.generated.rs", false), TriState::False);
+//! assert_eq!(rules.evaluate("docs/guide/intro.md", false), TriState::True);
+//! assert_eq!(rules.evaluate("src/docs/intro.md", false), TriState::Default);
+//!
+//! let rule = rules.matched("src/parser.generated.rs", false).unwrap();
+//! assert_eq!((rule.line(), rule.pattern()), (4, "!*.generated.rs"));
+//! ```
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::format;
+use std::path::Path;
+use std::str::FromStr;
+use std::string::String;
+use std::vec::Vec;
+
+use ::globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::TriState;
+
+/// A rule of a [`RuleSet`]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Rule {
+    pattern: String,
+    line: usize,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool
+}
+
+impl Rule {
+    /// Returns the rule as written, without trailing spaces
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the line the rule is on, counting from 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns true if the rule starts with `!`
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Returns true if the rule ends with `/`, so that it only matches directories
+    pub fn is_dir_only(&self) -> bool {
+        self.dir_only
+    }
+
+    /// Returns true if the rule has a `/` other than at its end, so that it matches relative to
+    /// the root rather than at any depth
+    pub fn is_anchored(&self) -> bool {
+        self.anchored
+    }
+
+    /// Returns the value of paths the rule decides, which is `TriState::False` if it is negated
+    /// and `TriState::True` otherwise
+    pub fn value(&self) -> TriState {
+        if self.negated { TriState::False } else { TriState::True }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Ordered glob rules in the syntax of `.gitignore` files
+///
+/// Each line holds a rule, and blank lines and lines starting with `#` are skipped. A rule
+/// starting with `!` is negated, a rule ending with `/` only matches directories, and a rule
+/// with any other `/` is anchored to the root while one without matches at any depth. Trailing
+/// spaces are ignored unless escaped with a backslash, as are a leading `#` or `!` meant
+/// literally. A `*` does not match `/`, but `**` matches any number of directories.
+///
+/// A rule matches a path if it matches the path or any of its parent directories, so a rule for
+/// a directory applies to everything in it. Unlike git, a later rule can still decide a path
+/// inside a directory an earlier rule matched.
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::globset::RuleSet;
+///
+/// let rules = RuleSet::new(["build/", "!build/keep.txt", "/*.log", "\\#notes"]).unwrap();
+///
+/// assert_eq!(rules.evaluate("build", true), TriState::True);
+/// assert_eq!(rules.evaluate("build", false), TriState::Default);
+/// assert_eq!(rules.evaluate("build/out/app", false), TriState::True);
+/// assert_eq!(rules.evaluate("build/keep.txt", false), TriState::False);
+/// assert_eq!(rules.evaluate("server.log", false), TriState::True);
+/// assert_eq!(rules.evaluate("logs/server.log", false), TriState::Default);
+/// assert_eq!(rules.evaluate("docs/#notes", false), TriState::True);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    set: GlobSet
+}
+
+impl RuleSet {
+    /// Returns the rules on the given lines
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(lines: I) -> Result<Self, ParseRuleError> {
+        let mut rules = Vec::new();
+        let mut builder = GlobSetBuilder::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            let text = trim_trailing_spaces(line.as_ref());
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match text.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, text)
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern)
+            };
+            let anchored = pattern.contains('/');
+            let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let glob = if anchored { pattern.into() } else { format!("**/{}", pattern) };
+            let glob = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .backslash_escape(true)
+                .build()
+                .map_err(|source| ParseRuleError { rule: Some((index + 1, text.into())), source })?;
+            builder.add(glob);
+            rules.push(Rule { pattern: text.into(), line: index + 1, negated, dir_only, anchored });
+        }
+
+        let set = builder.build().map_err(|source| ParseRuleError { rule: None, source })?;
+        Ok(Self { rules, set })
+    }
+
+    /// Returns the rules in order
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Returns the number of rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the last rule that matches a path relative to the root of the rules, or any of
+    /// its parent directories
+    pub fn matched<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> Option<&Rule> {
+        let path = path.as_ref();
+        let parents = path.ancestors().skip(1).filter(|parent| !parent.as_os_str().is_empty());
+        let mut matches = Vec::new();
+        let mut last = None;
+        for (candidate, is_dir) in std::iter::once((path, is_dir)).chain(parents.map(|parent| (parent, true))) {
+            self.set.matches_into(candidate, &mut matches);
+            let found = matches.iter().copied().filter(|&index| is_dir || !self.rules[index].dir_only).max();
+            last = last.max(found);
+        }
+        last.map(|index| &self.rules[index])
+    }
+
+    /// Returns the value of the last rule that matches a path relative to the root of the rules,
+    /// or any of its parent directories, or `TriState::Default` if none does
+    pub fn evaluate<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> TriState {
+        self.matched(path, is_dir).map_or(TriState::Default, Rule::value)
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = ParseRuleError;
+
+    /// Parses the rules on the lines of a string, such as the contents of a `.gitignore` file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.lines())
+    }
+}
+
+/// The error returned when a rule of a [`RuleSet`] is not a valid glob, or the rules cannot be
+/// combined into a set
+///
+/// ```rust
+/// use tristate::globset::RuleSet;
+///
+/// let error = "*.rs\nsrc/[a-".parse::<RuleSet>().unwrap_err();
+/// assert_eq!((error.line(), error.pattern()), (Some(2), Some("src/[a-")));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseRuleError {
+    rule: Option<(usize, String)>,
+    source: ::globset::Error
+}
+
+impl ParseRuleError {
+    /// Returns the line of the rule at fault, counting from 1, or `None` if the error concerns
+    /// the set as a whole
+    pub fn line(&self) -> Option<usize> {
+        self.rule.as_ref().map(|(line, _)| *line)
+    }
+
+    /// Returns the rule at fault as written, or `None` if the error concerns the set as a whole
+    pub fn pattern(&self) -> Option<&str> {
+        self.rule.as_ref().map(|(_, pattern)| pattern.as_str())
+    }
+}
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match &self.rule {
+            Some((line, pattern)) => write!(f, "invalid rule {:?} on line {}: {}", pattern, line, self.source),
+            None => write!(f, "invalid rules: {}", self.source)
+        }
+    }
+}
+
+impl Error for ParseRuleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Returns a line without its line ending and the spaces after it that are not escaped
+fn trim_trailing_spaces(line: &str) -> &str {
+    let mut text = line.trim_end_matches(['\n', '\r']);
+    while let Some(rest) = text.strip_suffix(' ') {
+        if rest.ends_with('\\') {
+            break;
+        }
+        text = rest;
+    }
+    text
+}
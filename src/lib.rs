@@ -10,19 +10,135 @@
 //! ```rust
 //! tristate::TriState::from(Some(true));
 //! ```
+//!
+//! # Logical operators
+//!
+//! `TriState` implements `!`, `&`, `|` and `^` (along with their assigning forms) using strong
+//! Kleene three-valued logic, where `TriState::Default` stands for an unknown value. A definite
+//! operand only wins when it decides the result on its own.
+//!
+//! ```rust
+//! use tristate::TriState;
+//!
+//! assert_eq!(TriState::False & TriState::Default, TriState::False);
+//! assert_eq!(TriState::True | TriState::Default, TriState::True);
+//! assert_eq!(TriState::True & TriState::Default, TriState::Default);
+//! ```
+//!
+//! The usual lattice laws such as De Morgan's hold over every combination of values, while the
+//! law of excluded middle does not:
+//!
+//! ```rust
+//! use tristate::TriState;
+//!
+//! assert_eq!(!(TriState::True & TriState::Default), !TriState::True | !TriState::Default);
+//! assert_eq!(TriState::Default | !TriState::Default, TriState::Default);
+//! ```
+//!
+//! Other three-valued logics, such as weak Kleene or Łukasiewicz logic, are available through
+//! the [`logic`] module.
+//!
+//! # Features
+//!
+//! The crate is `no_std` and its core types only need `core`. The following features are
+//! available:
+//!
+//! - `std` (default) enables the [`env`](mod@env) and [`flags`] modules, and implies `alloc`
+//! - `alloc` enables the types that allocate, such as [`TriStateVec`], [`TriStateParser`],
+//!   [`cascade::Cascade`] and [`TriMerge`]
+//! - `serde` (default) implements `Serialize` and `Deserialize`, and enables the [`serde`] module
+//! - `derive` enables `#[derive(TriMerge)]`
+//! - `bitflags` enables the `bitflags` module for sets of tri-state flags
+//! - `clap` enables the `clap` module for command line arguments
+//! - `globset` enables the `globset` module for path rules in the syntax of `.gitignore` files
+//! - `json` enables the `merge_patch` module for JSON Merge Patch documents
+//!
+//! The `atomic` module needs atomic read-modify-write operations on bytes, so it is only
+//! available on targets with `target_has_atomic = "8"`. Targets limited to atomic loads and
+//! stores, such as `thumbv6m-none-eabi`, get the rest of the crate without it.
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(target_has_atomic = "8")]
+pub mod atomic;
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
+#[cfg(feature = "alloc")]
+pub mod cascade;
+#[cfg(feature = "alloc")]
+pub mod check_tree;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "alloc")]
+pub mod expr;
+#[cfg(feature = "std")]
+pub mod flags;
+#[cfg(feature = "globset")]
+pub mod globset;
+pub mod logic;
+#[cfg(feature = "alloc")]
+pub mod merge;
+#[cfg(feature = "json")]
+pub mod merge_patch;
+mod ops;
+#[cfg(feature = "alloc")]
+pub mod packed;
+#[cfg(feature = "alloc")]
+pub mod parse;
+pub mod patch;
+#[cfg(feature = "alloc")]
+pub mod policy;
+#[cfg(feature = "alloc")]
+pub mod sat;
+#[cfg(feature = "alloc")]
+pub mod scoped;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+pub mod table;
+pub mod ternary;
+
+#[cfg(feature = "alloc")]
+pub use merge::TriMerge;
+#[cfg(feature = "alloc")]
+pub use packed::TriStateVec;
+#[cfg(feature = "alloc")]
+pub use parse::{ParseTriStateError, TriStateParser};
+pub use patch::Patch;
 
-use std::fmt::Display;
-use std::fmt::Formatter;
-use serde::{Serialize, Deserialize};
+#[cfg(feature = "derive")]
+pub use tristate_derive::TriMerge;
+
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+}
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+#[cfg(feature = "serde")]
+use ::serde::{Serialize, Deserialize};
 
 /// Represents a enum value that can be either true, false, or represent a default value
 ///
 /// An alternative to `Option<bool>`
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TriState {
     /// Represents the boolean value of `false`
     False,
     /// Represents a fallback to a "default" value
+    #[default]
     Default,
     /// Represents the boolean value of `true`
     True
@@ -67,14 +183,8 @@ impl From<Option<bool>> for TriState {
     }
 }
 
-impl Default for TriState {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 impl Display for TriState {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
         write!(f, "{:?}", self)
     }
 }
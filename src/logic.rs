@@ -0,0 +1,335 @@
+//! Pluggable three-valued logic systems.
+//!
+//! Each system gives its own meaning to `TriState::Default` by defining the connectives over
+//! `TriState` through the [`Logic`] trait. The [`Logical`] wrapper then carries the choice of
+//! system in its type, so the usual operators follow that system and code can be written
+//! generically over logics.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::logic::{Logic, Logical, Lukasiewicz, StrongKleene, WeakKleene};
+//!
+//! fn both<L: Logic>(a: TriState, b: TriState) -> TriState {
+//!     (Logical::<L>::new(a) & Logical::new(b)).get()
+//! }
+//!
+//! assert_eq!(both::<StrongKleene>(TriState::False, TriState::Default), TriState::False);
+//! assert_eq!(both::<WeakKleene>(TriState::False, TriState::Default), TriState::Default);
+//! assert_eq!(Lukasiewicz::implies(TriState::Default, TriState::Default), TriState::True);
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::TriState;
+
+/// A three-valued logic defining the connectives over `TriState`
+///
+/// Only conjunction, disjunction and negation are required, the remaining connectives default
+/// to their usual definitions in terms of those.
+pub trait Logic {
+    /// Returns the conjunction of two values
+    fn and(a: TriState, b: TriState) -> TriState;
+
+    /// Returns the disjunction of two values
+    fn or(a: TriState, b: TriState) -> TriState;
+
+    /// Returns the negation of a value
+    fn not(a: TriState) -> TriState;
+
+    /// Returns the implication `a -> b`, by default `!a | b`
+    fn implies(a: TriState, b: TriState) -> TriState {
+        Self::or(Self::not(a), b)
+    }
+
+    /// Returns the equivalence `a <-> b`, by default `(a -> b) & (b -> a)`
+    fn equiv(a: TriState, b: TriState) -> TriState {
+        Self::and(Self::implies(a, b), Self::implies(b, a))
+    }
+
+    /// Returns the exclusive disjunction of two values, by default `!(a <-> b)`
+    fn xor(a: TriState, b: TriState) -> TriState {
+        Self::not(Self::equiv(a, b))
+    }
+}
+
+/// Strong Kleene logic, where `TriState::Default` is an unknown value that a definite operand
+/// can still decide, such as `false & unknown = false`
+///
+/// These are the semantics of the operators implemented directly on `TriState`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct StrongKleene;
+
+impl Logic for StrongKleene {
+    fn and(a: TriState, b: TriState) -> TriState {
+        // `False < Default < True`, so conjunction is the minimum
+        a.min(b)
+    }
+
+    fn or(a: TriState, b: TriState) -> TriState {
+        a.max(b)
+    }
+
+    fn not(a: TriState) -> TriState {
+        match a {
+            TriState::False => TriState::True,
+            TriState::Default => TriState::Default,
+            TriState::True => TriState::False
+        }
+    }
+}
+
+/// Weak Kleene (Bochvar) logic, where `TriState::Default` is a meaningless value that spreads
+/// to the result of every connective it takes part in
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::logic::{Logic, WeakKleene};
+///
+/// assert_eq!(WeakKleene::or(TriState::True, TriState::Default), TriState::Default);
+/// assert_eq!(WeakKleene::or(TriState::True, TriState::False), TriState::True);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WeakKleene;
+
+impl WeakKleene {
+    fn definite(a: TriState, b: TriState, f: fn (bool, bool) -> bool) -> TriState {
+        match (Option::<bool>::from(a), Option::<bool>::from(b)) {
+            (Some(a), Some(b)) => TriState::from(f(a, b)),
+            _ => TriState::Default
+        }
+    }
+}
+
+impl Logic for WeakKleene {
+    fn and(a: TriState, b: TriState) -> TriState {
+        Self::definite(a, b, |a, b| a & b)
+    }
+
+    fn or(a: TriState, b: TriState) -> TriState {
+        Self::definite(a, b, |a, b| a | b)
+    }
+
+    fn not(a: TriState) -> TriState {
+        StrongKleene::not(a)
+    }
+}
+
+/// Łukasiewicz logic, which shares its conjunction, disjunction and negation with strong Kleene
+/// logic but treats `unknown -> unknown` as true
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::logic::{Logic, Lukasiewicz, StrongKleene};
+///
+/// assert_eq!(Lukasiewicz::implies(TriState::Default, TriState::Default), TriState::True);
+/// assert_eq!(StrongKleene::implies(TriState::Default, TriState::Default), TriState::Default);
+/// assert_eq!(Lukasiewicz::implies(TriState::Default, TriState::False), TriState::Default);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Lukasiewicz;
+
+impl Logic for Lukasiewicz {
+    fn and(a: TriState, b: TriState) -> TriState {
+        StrongKleene::and(a, b)
+    }
+
+    fn or(a: TriState, b: TriState) -> TriState {
+        StrongKleene::or(a, b)
+    }
+
+    fn not(a: TriState) -> TriState {
+        StrongKleene::not(a)
+    }
+
+    fn implies(a: TriState, b: TriState) -> TriState {
+        // Truth is `min(1, 1 - a + b)`, which is only short of true when `a` exceeds `b`
+        if a <= b { TriState::True } else { StrongKleene::or(StrongKleene::not(a), b) }
+    }
+}
+
+/// SQL's three-valued logic, where `TriState::Default` plays the role of `NULL`
+///
+/// The `AND`, `OR` and `NOT` operators of SQL follow strong Kleene logic, and SQL has no
+/// implication operator of its own, so this logic agrees with [`StrongKleene`] everywhere. It
+/// exists so that code can state that it follows SQL semantics.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Sql;
+
+impl Logic for Sql {
+    fn and(a: TriState, b: TriState) -> TriState {
+        StrongKleene::and(a, b)
+    }
+
+    fn or(a: TriState, b: TriState) -> TriState {
+        StrongKleene::or(a, b)
+    }
+
+    fn not(a: TriState) -> TriState {
+        StrongKleene::not(a)
+    }
+}
+
+/// A `TriState` whose operators follow the logic `L`
+///
+/// The wrapper has the same representation as `TriState`, so it costs nothing over the bare value.
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::logic::{Logical, WeakKleene};
+///
+/// let a = Logical::<WeakKleene>::new(TriState::False);
+/// assert_eq!((a & TriState::Default.into()).get(), TriState::Default);
+/// ```
+///
+/// The wrapper is `Copy`, `Eq`, `Ord`, `Hash` and `Default` whatever the logic, so a logic needs
+/// no derives of its own.
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::logic::{Logic, Logical};
+///
+/// struct Majority;
+///
+/// impl Logic for Majority {
+///     fn and(a: TriState, b: TriState) -> TriState { a.min(b) }
+///     fn or(a: TriState, b: TriState) -> TriState { a.max(b) }
+///     fn not(a: TriState) -> TriState { !a }
+/// }
+///
+/// let a = Logical::<Majority>::new(TriState::True);
+/// let b = a;
+/// assert_eq!(a, b);
+/// assert!(Logical::<Majority>::default() < a);
+/// ```
+#[repr(transparent)]
+pub struct Logical<L>(TriState, PhantomData<L>);
+
+/// A `TriState` following strong Kleene logic
+pub type Kleene = Logical<StrongKleene>;
+
+impl<L: Logic> Logical<L> {
+    /// Wraps a tri-state so that its operators follow the logic `L`
+    pub const fn new(value: TriState) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// Returns the wrapped tri-state
+    pub const fn get(self) -> TriState {
+        self.0
+    }
+
+    /// Returns the implication `self -> other` under the logic `L`
+    pub fn implies(self, other: Self) -> Self {
+        Self::new(L::implies(self.0, other.0))
+    }
+
+    /// Returns the equivalence `self <-> other` under the logic `L`
+    pub fn equiv(self, other: Self) -> Self {
+        Self::new(L::equiv(self.0, other.0))
+    }
+}
+
+impl<L: Logic> From<TriState> for Logical<L> {
+    fn from(value: TriState) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<L: Logic> From<Logical<L>> for TriState {
+    fn from(value: Logical<L>) -> Self {
+        value.0
+    }
+}
+
+// Implemented by hand since deriving would place the same bounds on the marker type `L`
+impl<L> Clone for Logical<L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L> Copy for Logical<L> {}
+
+impl<L> PartialEq for Logical<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<L> Eq for Logical<L> {}
+
+impl<L> PartialOrd for Logical<L> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<L> Ord for Logical<L> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<L> Hash for Logical<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<L> Default for Logical<L> {
+    fn default() -> Self {
+        Self(TriState::Default, PhantomData)
+    }
+}
+
+impl<L> AsRef<TriState> for Logical<L> {
+    fn as_ref(&self) -> &TriState {
+        &self.0
+    }
+}
+
+impl<L> Debug for Logical<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<L> Display for Logical<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<L: Logic> Not for Logical<L> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::new(L::not(self.0))
+    }
+}
+
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $f:ident) => {
+        impl<L: Logic> $op for Logical<L> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self::new(L::$f(self.0, rhs.0))
+            }
+        }
+
+        impl<L: Logic> $assign for Logical<L> {
+            fn $assign_method(&mut self, rhs: Self) {
+                self.0 = L::$f(self.0, rhs.0);
+            }
+        }
+    };
+}
+
+binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign, and);
+binary_op!(BitOr, bitor, BitOrAssign, bitor_assign, or);
+binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
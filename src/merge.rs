@@ -0,0 +1,78 @@
+//! Merging of structures made of tri-states.
+//!
+//! Configuration is commonly held in structures of `TriState` fields that are merged together
+//! from several sources and finally resolved into plain booleans. The [`TriMerge`] trait
+//! describes those operations, and with the `derive` feature enabled it can be derived for
+//! structures whose fields all implement it, including nested structures.
+//!
+//! ```rust
+//! use tristate::{TriMerge, TriState};
+//!
+//! let mut color = TriState::Default;
+//! color.overlay(&TriState::True);
+//! color.underlay(&TriState::False);
+//!
+//! assert_eq!(color, TriState::True);
+//! assert_eq!(TriState::Default.resolve(&true), true);
+//! assert_eq!(TriState::Default.unresolved(), vec![String::new()]);
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::TriState;
+
+/// A value built from tri-states that can be merged with another and resolved to booleans
+pub trait TriMerge {
+    /// The fully resolved form of the value, where every tri-state has become a `bool`
+    type Resolved;
+
+    /// Merges `other` on top of this value, so that its definite values win
+    fn overlay(&mut self, other: &Self);
+
+    /// Merges `other` beneath this value, so that it only fills in values that are still
+    /// `TriState::Default`
+    fn underlay(&mut self, other: &Self);
+
+    /// Returns the resolved form of the value, taking the values that are still
+    /// `TriState::Default` from `defaults`
+    fn resolve(&self, defaults: &Self::Resolved) -> Self::Resolved;
+
+    /// Adds the paths of every value that is still `TriState::Default` to `out`, with each path
+    /// made of field names joined by `.` after the given `prefix`
+    fn unresolved_into(&self, prefix: &str, out: &mut Vec<String>);
+
+    /// Returns the paths of every value that is still `TriState::Default`
+    fn unresolved(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.unresolved_into("", &mut out);
+        out
+    }
+}
+
+impl TriMerge for TriState {
+    type Resolved = bool;
+
+    fn overlay(&mut self, other: &Self) {
+        if other != &TriState::Default {
+            *self = *other;
+        }
+    }
+
+    fn underlay(&mut self, other: &Self) {
+        if self == &TriState::Default {
+            *self = *other;
+        }
+    }
+
+    fn resolve(&self, defaults: &bool) -> bool {
+        self.or_else(*defaults)
+    }
+
+    fn unresolved_into(&self, prefix: &str, out: &mut Vec<String>) {
+        if self == &TriState::Default {
+            out.push(prefix.to_owned());
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! JSON Merge Patch ([RFC 7396](https://www.rfc-editor.org/rfc/rfc7396)), available with the
+//! `json` feature.
+//!
+//! A merge patch describes each member of a document with the same three-way distinction as
+//! [`Patch`]: a member that is missing from the patch is left unchanged, a `null` member removes
+//! it, and any other member sets it, merging recursively into objects.
+//!
+//! ```rust
+//! use serde_json::json;
+//! use tristate::merge_patch;
+//!
+//! let mut document = json!({
+//!     "title": "Goodbye!",
+//!     "author": { "givenName": "John", "familyName": "Doe" },
+//!     "tags": ["example", "sample"],
+//!     "content": "This will be unchanged"
+//! });
+//! let patch = json!({
+//!     "title": "Hello!",
+//!     "phoneNumber": "+01-123-456-7890",
+//!     "author": { "familyName": null },
+//!     "tags": ["example"]
+//! });
+//!
+//! let original = document.clone();
+//! merge_patch::apply(&mut document, &patch);
+//! assert_eq!(document, json!({
+//!     "title": "Hello!",
+//!     "author": { "givenName": "John" },
+//!     "tags": ["example"],
+//!     "content": "This will be unchanged",
+//!     "phoneNumber": "+01-123-456-7890"
+//! }));
+//! assert_eq!(merge_patch::diff(&original, &document), Some(patch));
+//! ```
+//!
+//! The test vectors from appendix A of the RFC:
+//!
+//! ```rust
+//! use serde_json::json;
+//! use tristate::merge_patch;
+//!
+//! let vectors = [
+//!     (json!({"a":"b"}), json!({"a":"c"}), json!({"a":"c"})),
+//!     (json!({"a":"b"}), json!({"b":"c"}), json!({"a":"b","b":"c"})),
+//!     (json!({"a":"b"}), json!({"a":null}), json!({})),
+//!     (json!({"a":"b","b":"c"}), json!({"a":null}), json!({"b":"c"})),
+//!     (json!({"a":["b"]}), json!({"a":"c"}), json!({"a":"c"})),
+//!     (json!({"a":"c"}), json!({"a":["b"]}), json!({"a":["b"]})),
+//!     (json!({"a":{"b":"c"}}), json!({"a":{"b":"d","c":null}}), json!({"a":{"b":"d"}})),
+//!     (json!({"a":[{"b":"c"}]}), json!({"a":[1]}), json!({"a":[1]})),
+//!     (json!(["a","b"]), json!(["c","d"]), json!(["c","d"])),
+//!     (json!({"a":"b"}), json!(["c"]), json!(["c"])),
+//!     (json!({"a":"foo"}), json!(null), json!(null)),
+//!     (json!({"a":"foo"}), json!("bar"), json!("bar")),
+//!     (json!({"e":null}), json!({"a":1}), json!({"e":null,"a":1})),
+//!     (json!([1,2]), json!({"a":"b","c":null}), json!({"a":"b"})),
+//!     (json!({}), json!({"a":{"bb":{"ccc":null}}}), json!({"a":{"bb":{}}}))
+//! ];
+//!
+//! for (original, patch, result) in vectors.iter() {
+//!     let mut document = original.clone();
+//!     merge_patch::apply(&mut document, patch);
+//!     assert_eq!(&document, result);
+//!
+//!     // The computed patch reproduces the result, though it may differ from the given one
+//!     let mut document = original.clone();
+//!     merge_patch::apply(&mut document, &merge_patch::diff(original, result).unwrap());
+//!     assert_eq!(&document, result);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::string::String;
+
+use ::serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::{Patch, TriState};
+
+/// Applies a merge patch to a document
+pub fn apply(target: &mut Value, patch: &Value) {
+    let members = match patch {
+        Value::Object(members) => members,
+        _ => {
+            *target = patch.clone();
+            return;
+        }
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (name, value) in members {
+            if value.is_null() {
+                target.remove(name);
+            } else {
+                apply(target.entry(name.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Returns the merge patch that turns `source` into `target`
+///
+/// A merge patch cannot set a member to `null`, since that would be read as a removal, so this
+/// returns `None` when `target` holds a `null` member that `source` does not.
+///
+/// ```rust
+/// use serde_json::json;
+/// use tristate::merge_patch;
+///
+/// assert_eq!(merge_patch::diff(&json!({"a":1,"b":2}), &json!({"a":1,"c":3})), Some(json!({"b":null,"c":3})));
+/// assert_eq!(merge_patch::diff(&json!({}), &json!({"a":{"b":null}})), None);
+/// assert_eq!(merge_patch::diff(&json!({"a":1}), &json!({"a":null})), None);
+/// ```
+pub fn diff(source: &Value, target: &Value) -> Option<Value> {
+    match (source, target) {
+        (Value::Object(source), Value::Object(target)) => {
+            let mut patch = Map::new();
+            for name in source.keys().filter(|name| !target.contains_key(*name)) {
+                patch.insert(name.clone(), Value::Null);
+            }
+            for (name, value) in target {
+                match source.get(name) {
+                    Some(previous) if previous == value => {}
+                    // A `null` member in a patch removes the member rather than setting it
+                    _ if value.is_null() => return None,
+                    Some(previous) => {
+                        patch.insert(name.clone(), diff(previous, value)?);
+                    }
+                    None => {
+                        patch.insert(name.clone(), replacement(value)?);
+                    }
+                }
+            }
+            Some(Value::Object(patch))
+        }
+        _ => replacement(target)
+    }
+}
+
+/// Returns the patch that sets a member to `value`, if it can be expressed
+fn replacement(value: &Value) -> Option<Value> {
+    match value {
+        Value::Object(members) if members.values().any(|member| member.is_null() || replacement(member).is_none()) => None,
+        _ => Some(value.clone())
+    }
+}
+
+/// Returns a single merge patch with the effect of applying `first` and then `second`
+///
+/// A merge patch merges objects rather than replacing them, so when `first` replaces a value
+/// with something other than an object and `second` then sets an object in its place, no single
+/// patch has the same effect on every document. This returns `None` in that case.
+///
+/// ```rust
+/// use serde_json::json;
+/// use tristate::merge_patch;
+///
+/// let first = json!({"a":{"b":1},"c":2});
+/// let second = json!({"a":{"d":3},"c":null});
+/// assert_eq!(merge_patch::compose(&first, &second), Some(json!({"a":{"b":1,"d":3},"c":null})));
+/// assert_eq!(merge_patch::compose(&json!({"a":1}), &json!({"a":{"b":2}})), None);
+/// ```
+pub fn compose(first: &Value, second: &Value) -> Option<Value> {
+    let (first, members) = match (first, second) {
+        (_, Value::Object(members)) if !members.is_empty() => (first, members),
+        // An empty object patch changes nothing besides turning a non-object into an object
+        (Value::Object(_), Value::Object(_)) => return Some(first.clone()),
+        (_, Value::Object(_)) => return None,
+        _ => return Some(second.clone())
+    };
+    let mut composed = match first {
+        Value::Object(first) => first.clone(),
+        _ => return None
+    };
+    for (name, value) in members {
+        let value = match (composed.get(name), value) {
+            (Some(previous @ Value::Object(_)), Value::Object(_)) => compose(previous, value)?,
+            (Some(_), Value::Object(_)) => return None,
+            _ => value.clone()
+        };
+        composed.insert(name.clone(), value);
+    }
+    Some(Value::Object(composed))
+}
+
+/// A merge patch viewed as a tree of [`Patch`] members
+///
+/// ```rust
+/// use serde_json::json;
+/// use tristate::{Patch, TriState};
+/// use tristate::merge_patch::PatchTree;
+///
+/// let tree = PatchTree::from(&json!({"display":{"color":true,"theme":null,"width":80}}));
+///
+/// assert_eq!(tree.flag(&["display", "color"]), Some(TriState::True));
+/// assert_eq!(tree.flag(&["display", "unicode"]), Some(TriState::Default));
+/// assert_eq!(tree.value::<String>(&["display", "theme"]).unwrap(), Patch::Null);
+/// assert_eq!(tree.value::<u32>(&["display", "width"]).unwrap(), Patch::Value(80));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PatchTree {
+    /// Merges into an object, where members missing from the map are left unchanged
+    Merge(BTreeMap<String, Patch<PatchTree>>),
+    /// Replaces the target with a value that is not an object
+    Replace(Value)
+}
+
+impl PatchTree {
+    /// Returns the patch for the member at `path`, which is `Patch::Absent` if the patch leaves
+    /// it unchanged
+    pub fn get(&self, path: &[&str]) -> Patch<&PatchTree> {
+        let (name, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Patch::Value(self)
+        };
+        match self {
+            Self::Merge(members) => match members.get(*name) {
+                Some(Patch::Value(tree)) => tree.get(rest),
+                // Members below a removed member are removed along with it
+                Some(Patch::Null) => Patch::Null,
+                Some(Patch::Absent) | None => Patch::Absent
+            },
+            // Replacing the target with a non-object removes every member beneath it
+            Self::Replace(_) => Patch::Null
+        }
+    }
+
+    /// Returns the typed patch for the member at `path`
+    pub fn value<T: DeserializeOwned>(&self, path: &[&str]) -> Result<Patch<T>, serde_json::Error> {
+        match self.get(path) {
+            Patch::Absent => Ok(Patch::Absent),
+            Patch::Null => Ok(Patch::Null),
+            Patch::Value(tree) => serde_json::from_value(Value::from(tree)).map(Patch::Value)
+        }
+    }
+
+    /// Returns the tri-state for a boolean member at `path`, which is `TriState::Default` if the
+    /// patch leaves it unchanged, or `None` if the patch removes it or sets something other than
+    /// a boolean
+    pub fn flag(&self, path: &[&str]) -> Option<TriState> {
+        match self.get(path) {
+            Patch::Absent => Some(TriState::Default),
+            Patch::Value(PatchTree::Replace(Value::Bool(value))) => Some(TriState::from(*value)),
+            _ => None
+        }
+    }
+}
+
+impl From<&Value> for PatchTree {
+    fn from(patch: &Value) -> Self {
+        match patch {
+            Value::Object(members) => Self::Merge(members.iter().map(|(name, value)| {
+                let member = if value.is_null() { Patch::Null } else { Patch::Value(Self::from(value)) };
+                (name.clone(), member)
+            }).collect()),
+            _ => Self::Replace(patch.clone())
+        }
+    }
+}
+
+impl From<&PatchTree> for Value {
+    fn from(tree: &PatchTree) -> Self {
+        match tree {
+            PatchTree::Merge(members) => Value::Object(members.iter().filter_map(|(name, member)| {
+                match member {
+                    Patch::Absent => None,
+                    Patch::Null => Some((name.clone(), Value::Null)),
+                    Patch::Value(tree) => Some((name.clone(), Value::from(tree)))
+                }
+            }).collect()),
+            PatchTree::Replace(value) => value.clone()
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Logical operators for `TriState` following strong Kleene three-valued logic, where
+//! `TriState::Default` stands for an unknown value.
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::logic::{Logic, StrongKleene};
+use crate::TriState;
+
+impl TriState {
+    /// Returns the strong Kleene material implication `!self | other`.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    ///
+    /// assert_eq!(TriState::False.implies(TriState::Default), TriState::True);
+    /// assert_eq!(TriState::Default.implies(TriState::True), TriState::True);
+    /// assert_eq!(TriState::Default.implies(TriState::Default), TriState::Default);
+    /// assert_eq!(TriState::True.implies(TriState::False), TriState::False);
+    /// ```
+    pub fn implies(&self, other: TriState) -> TriState {
+        !*self | other
+    }
+
+    /// Returns the strong Kleene equivalence `!(self ^ other)`, which is unknown whenever
+    /// either side is unknown.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    ///
+    /// assert_eq!(TriState::True.equiv(TriState::True), TriState::True);
+    /// assert_eq!(TriState::False.equiv(TriState::True), TriState::False);
+    /// assert_eq!(TriState::Default.equiv(TriState::Default), TriState::Default);
+    /// ```
+    pub fn equiv(&self, other: TriState) -> TriState {
+        !(*self ^ other)
+    }
+}
+
+impl Not for TriState {
+    type Output = TriState;
+
+    /// Returns the negation of the tri-state, leaving `TriState::Default` unchanged
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    ///
+    /// assert_eq!(!TriState::True, TriState::False);
+    /// assert_eq!(!TriState::False, TriState::True);
+    /// assert_eq!(!TriState::Default, TriState::Default);
+    /// ```
+    fn not(self) -> TriState {
+        StrongKleene::not(self)
+    }
+}
+
+impl Not for &TriState {
+    type Output = TriState;
+
+    fn not(self) -> TriState {
+        !*self
+    }
+}
+
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $f:ident) => {
+        impl $op<TriState> for TriState {
+            type Output = TriState;
+
+            fn $method(self, rhs: TriState) -> TriState {
+                StrongKleene::$f(self, rhs)
+            }
+        }
+
+        impl $op<&TriState> for TriState {
+            type Output = TriState;
+
+            fn $method(self, rhs: &TriState) -> TriState {
+                StrongKleene::$f(self, *rhs)
+            }
+        }
+
+        impl $op<TriState> for &TriState {
+            type Output = TriState;
+
+            fn $method(self, rhs: TriState) -> TriState {
+                StrongKleene::$f(*self, rhs)
+            }
+        }
+
+        impl $op<&TriState> for &TriState {
+            type Output = TriState;
+
+            fn $method(self, rhs: &TriState) -> TriState {
+                StrongKleene::$f(*self, *rhs)
+            }
+        }
+
+        impl $assign<TriState> for TriState {
+            fn $assign_method(&mut self, rhs: TriState) {
+                *self = StrongKleene::$f(*self, rhs);
+            }
+        }
+
+        impl $assign<&TriState> for TriState {
+            fn $assign_method(&mut self, rhs: &TriState) {
+                *self = StrongKleene::$f(*self, *rhs);
+            }
+        }
+    };
+}
+
+binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign, and);
+binary_op!(BitOr, bitor, BitOrAssign, bitor_assign, or);
+binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+
+#[cfg(test)]
+mod tests {
+    use crate::TriState;
+
+    const ALL: [TriState; 3] = [TriState::False, TriState::Default, TriState::True];
+
+    fn pairs() -> impl Iterator<Item = (TriState, TriState)> {
+        ALL.iter().flat_map(|&a| ALL.iter().map(move |&b| (a, b)))
+    }
+
+    fn triples() -> impl Iterator<Item = (TriState, TriState, TriState)> {
+        pairs().flat_map(|(a, b)| ALL.iter().map(move |&c| (a, b, c)))
+    }
+
+    #[test]
+    fn idempotence() {
+        for a in ALL {
+            assert_eq!(a & a, a);
+            assert_eq!(a | a, a);
+        }
+    }
+
+    #[test]
+    fn double_negation() {
+        for a in ALL {
+            assert_eq!(!!a, a);
+        }
+    }
+
+    #[test]
+    fn de_morgan() {
+        for (a, b) in pairs() {
+            assert_eq!(!(a & b), !a | !b);
+            assert_eq!(!(a | b), !a & !b);
+        }
+    }
+
+    #[test]
+    fn absorption() {
+        for (a, b) in pairs() {
+            assert_eq!(a & (a | b), a);
+            assert_eq!(a | (a & b), a);
+        }
+    }
+
+    #[test]
+    fn commutativity() {
+        for (a, b) in pairs() {
+            assert_eq!(a & b, b & a);
+            assert_eq!(a | b, b | a);
+            assert_eq!(a ^ b, b ^ a);
+        }
+    }
+
+    #[test]
+    fn associativity() {
+        for (a, b, c) in triples() {
+            assert_eq!((a & b) & c, a & (b & c));
+            assert_eq!((a | b) | c, a | (b | c));
+            assert_eq!((a ^ b) ^ c, a ^ (b ^ c));
+        }
+    }
+
+    #[test]
+    fn distributivity() {
+        for (a, b, c) in triples() {
+            assert_eq!(a & (b | c), (a & b) | (a & c));
+            assert_eq!(a | (b & c), (a | b) & (a | c));
+        }
+    }
+
+    #[test]
+    fn derived_connectives() {
+        for (a, b) in pairs() {
+            assert_eq!(a.implies(b), !a | b);
+            assert_eq!(a.equiv(b), a.implies(b) & b.implies(a));
+        }
+    }
+
+    #[test]
+    // The references are the point, to exercise the impls on `&TriState`
+    #[allow(clippy::op_ref)]
+    fn references_and_assignment_agree() {
+        for (a, b) in pairs() {
+            assert_eq!(!&a, !a);
+            assert_eq!(&a & &b, a & b);
+            assert_eq!(&a | b, a | b);
+            assert_eq!(a ^ &b, a ^ b);
+
+            let mut c = a;
+            c &= b;
+            assert_eq!(c, a & b);
+            let mut c = a;
+            c |= &b;
+            assert_eq!(c, a | b);
+            let mut c = a;
+            c ^= &b;
+            assert_eq!(c, a ^ b);
+        }
+    }
+
+    #[test]
+    fn classical_on_definite_values() {
+        for (x, y) in [(false, false), (false, true), (true, false), (true, true)] {
+            let (a, b) = (TriState::from(x), TriState::from(y));
+            assert_eq!(!a, TriState::from(!x));
+            assert_eq!(a & b, TriState::from(x & y));
+            assert_eq!(a | b, TriState::from(x | y));
+            assert_eq!(a ^ b, TriState::from(x ^ y));
+        }
+    }
+
+    #[test]
+    fn no_excluded_middle() {
+        assert_eq!(TriState::Default | !TriState::Default, TriState::Default);
+        assert_eq!(TriState::Default & !TriState::Default, TriState::Default);
+    }
+}
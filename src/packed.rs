@@ -0,0 +1,533 @@
+//! Packed storage for large numbers of tri-states.
+//!
+//! [`TriStateVec`] stores each value in two bits rather than the byte used by `Vec<TriState>`.
+//! Every value is kept as a pair of flags, one set when the value is `TriState::True` and one
+//! set when it is `TriState::False`, with neither set for `TriState::Default`. This layout lets
+//! the strong Kleene connectives and the value counts work on 32 values per machine word.
+//!
+//! The bulk operations agree with the operators on single values:
+//!
+//! ```rust
+//! use tristate::{TriState, TriStateVec};
+//!
+//! const ALL: [TriState; 3] = [TriState::False, TriState::Default, TriState::True];
+//!
+//! // Every pair of values, repeated so that the vectors span several words
+//! let pairs: Vec<(TriState, TriState)> = (0..10)
+//!     .flat_map(|_| ALL.iter().flat_map(|&a| ALL.iter().map(move |&b| (a, b))))
+//!     .collect();
+//! let a: TriStateVec = pairs.iter().map(|p| p.0).collect();
+//! let b: TriStateVec = pairs.iter().map(|p| p.1).collect();
+//!
+//! assert!((&a & &b).iter().eq(pairs.iter().map(|&(a, b)| a & b)));
+//! assert!((&a | &b).iter().eq(pairs.iter().map(|&(a, b)| a | b)));
+//! assert!((!&a).iter().eq(pairs.iter().map(|&(a, _)| !a)));
+//! assert_eq!(a.count_true() + a.count_false() + a.count_default(), a.len());
+//! assert_eq!(a.slice(5..70).count_true(), a.iter().skip(5).take(65).filter(|&v| v == TriState::True).count());
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::iter::FromIterator;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Bound, Not, RangeBounds};
+
+use crate::TriState;
+
+/// Number of values packed into each word
+const PER_WORD: usize = 32;
+/// Mask of the flags set for `TriState::True` values
+const TRUE_BITS: u64 = 0x5555_5555_5555_5555;
+/// Mask of the flags set for `TriState::False` values
+const FALSE_BITS: u64 = 0xAAAA_AAAA_AAAA_AAAA;
+
+fn encode(value: TriState) -> u64 {
+    match value {
+        TriState::False => 0b10,
+        TriState::Default => 0b00,
+        TriState::True => 0b01
+    }
+}
+
+fn decode(bits: u64) -> TriState {
+    match bits & 0b11 {
+        0b01 => TriState::True,
+        0b10 => TriState::False,
+        _ => TriState::Default
+    }
+}
+
+fn read(words: &[u64], index: usize) -> TriState {
+    decode(words[index / PER_WORD] >> (index % PER_WORD * 2))
+}
+
+/// Counts the flags selected by `mask` for the values in `start..end`
+fn count(words: &[u64], start: usize, end: usize, mask: u64) -> usize {
+    if start >= end {
+        return 0;
+    }
+    let (first, last) = (start / PER_WORD, (end - 1) / PER_WORD);
+    (first..=last).map(|i| {
+        let mut word = words[i] & mask;
+        if i == first {
+            word &= !0 << (start % PER_WORD * 2);
+        }
+        if i == last && !end.is_multiple_of(PER_WORD) {
+            word &= !(!0 << (end % PER_WORD * 2));
+        }
+        word.count_ones() as usize
+    }).sum()
+}
+
+/// A growable vector of tri-states packed two bits per value
+///
+/// ```rust
+/// use tristate::{TriState, TriStateVec};
+///
+/// let mut flags = TriStateVec::new();
+/// flags.push(TriState::True);
+/// flags.push(TriState::Default);
+/// flags.push(TriState::False);
+///
+/// assert_eq!(flags.len(), 3);
+/// assert_eq!(flags.get(1), Some(TriState::Default));
+/// assert_eq!(flags.count_true(), 1);
+///
+/// flags.set(1, TriState::True);
+/// assert_eq!(Vec::<TriState>::from(flags), vec![TriState::True, TriState::True, TriState::False]);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct TriStateVec {
+    /// Packed values, where every value at or past `len` is kept as `TriState::Default`
+    words: Vec<u64>,
+    len: usize
+}
+
+impl TriStateVec {
+    /// Returns a new empty vector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new empty vector with room for at least `capacity` values
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { words: Vec::with_capacity(capacity.div_ceil(PER_WORD)), len: 0 }
+    }
+
+    /// Returns a vector of `len` copies of `value`
+    ///
+    /// ```rust
+    /// use tristate::{TriState, TriStateVec};
+    ///
+    /// let flags = TriStateVec::repeat(TriState::False, 40);
+    /// assert_eq!(flags.count_false(), 40);
+    /// ```
+    pub fn repeat(value: TriState, len: usize) -> Self {
+        let pattern = match value {
+            TriState::False => FALSE_BITS,
+            TriState::Default => 0,
+            TriState::True => TRUE_BITS
+        };
+        let mut words = vec![pattern; len.div_ceil(PER_WORD)];
+        if let Some(last) = words.last_mut() {
+            if !len.is_multiple_of(PER_WORD) {
+                *last &= !(!0 << (len % PER_WORD * 2));
+            }
+        }
+        Self { words, len }
+    }
+
+    /// Returns the number of values in the vector
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the vector holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `index`, or `None` if it is out of bounds
+    pub fn get(&self, index: usize) -> Option<TriState> {
+        if index < self.len { Some(read(&self.words, index)) } else { None }
+    }
+
+    /// Replaces the value at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: TriState) {
+        assert!(index < self.len, "index {} out of bounds for length {}", index, self.len);
+        let shift = index % PER_WORD * 2;
+        let word = &mut self.words[index / PER_WORD];
+        *word = (*word & !(0b11 << shift)) | (encode(value) << shift);
+    }
+
+    /// Appends a value to the end of the vector
+    pub fn push(&mut self, value: TriState) {
+        if self.len.is_multiple_of(PER_WORD) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// Removes and returns the last value, or `None` if the vector is empty
+    pub fn pop(&mut self) -> Option<TriState> {
+        let value = self.get(self.len.checked_sub(1)?)?;
+        self.set(self.len - 1, TriState::Default);
+        self.len -= 1;
+        if self.len.is_multiple_of(PER_WORD) {
+            self.words.pop();
+        }
+        Some(value)
+    }
+
+    /// Removes every value from the vector
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the values in the vector
+    pub fn iter(&self) -> Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    /// Returns a view of the whole vector
+    pub fn as_slice(&self) -> TriStateSlice<'_> {
+        TriStateSlice { words: &self.words, start: 0, len: self.len }
+    }
+
+    /// Returns a view of the values within `range`
+    ///
+    /// ```rust
+    /// use tristate::{TriState, TriStateVec};
+    ///
+    /// let flags: TriStateVec = vec![TriState::True, TriState::False, TriState::Default].into();
+    /// let tail = flags.slice(1..);
+    ///
+    /// assert_eq!(tail.len(), 2);
+    /// assert_eq!(tail.get(0), Some(TriState::False));
+    /// assert_eq!(tail.count_default(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or decreasing.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> TriStateSlice<'_> {
+        self.as_slice().slice(range)
+    }
+
+    /// Returns the number of `TriState::True` values
+    pub fn count_true(&self) -> usize {
+        self.words.iter().map(|word| (word & TRUE_BITS).count_ones() as usize).sum()
+    }
+
+    /// Returns the number of `TriState::False` values
+    pub fn count_false(&self) -> usize {
+        self.words.iter().map(|word| (word & FALSE_BITS).count_ones() as usize).sum()
+    }
+
+    /// Returns the number of `TriState::Default` values
+    pub fn count_default(&self) -> usize {
+        self.len - self.words.iter().map(|word| word.count_ones() as usize).sum::<usize>()
+    }
+
+    /// Replaces every value with its strong Kleene conjunction with the value at the same index
+    /// in `other`
+    ///
+    /// ```rust
+    /// use tristate::{TriState, TriStateVec};
+    ///
+    /// let mut a = TriStateVec::repeat(TriState::True, 3);
+    /// let b: TriStateVec = vec![TriState::True, TriState::Default, TriState::False].into();
+    /// a.and(&b);
+    /// assert_eq!(a, b);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors differ in length.
+    pub fn and(&mut self, other: &TriStateVec) {
+        // A value is only true if both are true, and false if either is false
+        self.zip_words(other, |a, b| (a & b & TRUE_BITS) | ((a | b) & FALSE_BITS));
+    }
+
+    /// Replaces every value with its strong Kleene disjunction with the value at the same index
+    /// in `other`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors differ in length.
+    pub fn or(&mut self, other: &TriStateVec) {
+        self.zip_words(other, |a, b| ((a | b) & TRUE_BITS) | (a & b & FALSE_BITS));
+    }
+
+    /// Negates every value, leaving `TriState::Default` values unchanged
+    pub fn not(&mut self) {
+        for word in &mut self.words {
+            *word = ((*word & TRUE_BITS) << 1) | ((*word & FALSE_BITS) >> 1);
+        }
+    }
+
+    fn zip_words(&mut self, other: &TriStateVec, f: fn (u64, u64) -> u64) {
+        assert_eq!(self.len, other.len, "tri-state vectors differ in length");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a = f(*a, *b);
+        }
+    }
+}
+
+impl Debug for TriStateVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Extend<TriState> for TriStateVec {
+    fn extend<I: IntoIterator<Item = TriState>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl FromIterator<TriState> for TriStateVec {
+    fn from_iter<I: IntoIterator<Item = TriState>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<'a> IntoIterator for &'a TriStateVec {
+    type Item = TriState;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl From<&[TriState]> for TriStateVec {
+    fn from(values: &[TriState]) -> Self {
+        values.iter().copied().collect()
+    }
+}
+
+impl From<Vec<TriState>> for TriStateVec {
+    fn from(values: Vec<TriState>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl From<Vec<Option<bool>>> for TriStateVec {
+    /// Returns the packed vector for a vector of optional booleans
+    ///
+    /// ```rust
+    /// use tristate::TriStateVec;
+    ///
+    /// let values = vec![Some(true), None, Some(false)];
+    /// let packed = TriStateVec::from(values.clone());
+    /// assert_eq!(Vec::<Option<bool>>::from(packed), values);
+    /// ```
+    fn from(values: Vec<Option<bool>>) -> Self {
+        values.into_iter().map(TriState::from).collect()
+    }
+}
+
+impl From<TriStateVec> for Vec<TriState> {
+    fn from(vec: TriStateVec) -> Self {
+        vec.iter().collect()
+    }
+}
+
+impl From<TriStateVec> for Vec<Option<bool>> {
+    fn from(vec: TriStateVec) -> Self {
+        vec.iter().map(Option::<bool>::from).collect()
+    }
+}
+
+impl From<TriStateSlice<'_>> for TriStateVec {
+    fn from(slice: TriStateSlice<'_>) -> Self {
+        slice.iter().collect()
+    }
+}
+
+impl Not for TriStateVec {
+    type Output = TriStateVec;
+
+    fn not(mut self) -> TriStateVec {
+        TriStateVec::not(&mut self);
+        self
+    }
+}
+
+impl Not for &TriStateVec {
+    type Output = TriStateVec;
+
+    fn not(self) -> TriStateVec {
+        !self.clone()
+    }
+}
+
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $f:ident) => {
+        impl $op<&TriStateVec> for &TriStateVec {
+            type Output = TriStateVec;
+
+            /// # Panics
+            ///
+            /// Panics if the vectors differ in length.
+            fn $method(self, rhs: &TriStateVec) -> TriStateVec {
+                let mut result = self.clone();
+                result.$f(rhs);
+                result
+            }
+        }
+
+        impl $assign<&TriStateVec> for TriStateVec {
+            /// # Panics
+            ///
+            /// Panics if the vectors differ in length.
+            fn $assign_method(&mut self, rhs: &TriStateVec) {
+                self.$f(rhs);
+            }
+        }
+    };
+}
+
+binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign, and);
+binary_op!(BitOr, bitor, BitOrAssign, bitor_assign, or);
+
+/// A borrowed view of a range of values in a [`TriStateVec`]
+#[derive(Copy, Clone)]
+pub struct TriStateSlice<'a> {
+    words: &'a [u64],
+    start: usize,
+    len: usize
+}
+
+impl<'a> TriStateSlice<'a> {
+    /// Returns the number of values in the slice
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the slice holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `index`, or `None` if it is out of bounds
+    pub fn get(&self, index: usize) -> Option<TriState> {
+        if index < self.len { Some(read(self.words, self.start + index)) } else { None }
+    }
+
+    /// Returns an iterator over the values in the slice
+    pub fn iter(&self) -> Iter<'a> {
+        Iter { slice: *self, front: 0, back: self.len }
+    }
+
+    /// Returns a view of the values within `range`, relative to the start of this slice
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or decreasing.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> TriStateSlice<'a> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len
+        };
+        assert!(start <= end, "slice index starts at {} but ends at {}", start, end);
+        assert!(end <= self.len, "range end index {} out of range for slice of length {}", end, self.len);
+        TriStateSlice { words: self.words, start: self.start + start, len: end - start }
+    }
+
+    /// Returns the number of `TriState::True` values
+    pub fn count_true(&self) -> usize {
+        count(self.words, self.start, self.start + self.len, TRUE_BITS)
+    }
+
+    /// Returns the number of `TriState::False` values
+    pub fn count_false(&self) -> usize {
+        count(self.words, self.start, self.start + self.len, FALSE_BITS)
+    }
+
+    /// Returns the number of `TriState::Default` values
+    pub fn count_default(&self) -> usize {
+        self.len - count(self.words, self.start, self.start + self.len, !0)
+    }
+
+    /// Copies the values into a new [`TriStateVec`]
+    pub fn to_vec(&self) -> TriStateVec {
+        TriStateVec::from(*self)
+    }
+}
+
+impl Debug for TriStateSlice<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for TriStateSlice<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for TriStateSlice<'_> {}
+
+impl<'a> IntoIterator for TriStateSlice<'a> {
+    type Item = TriState;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`TriStateVec`] or [`TriStateSlice`]
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    slice: TriStateSlice<'a>,
+    front: usize,
+    back: usize
+}
+
+impl Iterator for Iter<'_> {
+    type Item = TriState;
+
+    fn next(&mut self) -> Option<TriState> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.slice.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<TriState> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.slice.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
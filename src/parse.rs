@@ -0,0 +1,171 @@
+//! Parsing of tri-states from text.
+//!
+//! `TriState` implements `FromStr` for a standard vocabulary, ignoring ASCII case:
+//!
+//! | Value     | Accepted words                                  |
+//! |-----------|-------------------------------------------------|
+//! | `True`    | `true`, `yes`, `on`, `1`                        |
+//! | `False`   | `false`, `no`, `off`, `0`                       |
+//! | `Default` | `default`, `auto`, `inherit`, `-` and the empty string |
+//!
+//! A [`TriStateParser`] accepts a vocabulary of its own choosing.
+//!
+//! ```rust
+//! use tristate::TriState;
+//!
+//! assert_eq!("yes".parse(), Ok(TriState::True));
+//! assert_eq!("OFF".parse(), Ok(TriState::False));
+//! assert_eq!("".parse(), Ok(TriState::Default));
+//! assert_eq!(TriState::Default.to_string().parse(), Ok(TriState::Default));
+//!
+//! let error = "maybe".parse::<TriState>().unwrap_err();
+//! assert_eq!(error.input(), "maybe");
+//! assert!(error.to_string().starts_with("invalid tri-state \"maybe\", expected one of: true, yes,"));
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use crate::TriState;
+
+/// The vocabulary accepted by `TriState::from_str`
+const STANDARD: &[(&str, TriState)] = &[
+    ("true", TriState::True),
+    ("yes", TriState::True),
+    ("on", TriState::True),
+    ("1", TriState::True),
+    ("false", TriState::False),
+    ("no", TriState::False),
+    ("off", TriState::False),
+    ("0", TriState::False),
+    ("default", TriState::Default),
+    ("auto", TriState::Default),
+    ("inherit", TriState::Default),
+    ("-", TriState::Default),
+    ("", TriState::Default)
+];
+
+impl FromStr for TriState {
+    type Err = ParseTriStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        STANDARD.iter()
+            .find(|(word, _)| word.eq_ignore_ascii_case(s))
+            .map(|&(_, value)| value)
+            .ok_or_else(|| ParseTriStateError {
+                input: s.to_owned(),
+                accepted: STANDARD.iter().map(|(word, _)| (*word).to_owned()).collect()
+            })
+    }
+}
+
+/// A parser for tri-states with a configurable vocabulary
+///
+/// ```rust
+/// use tristate::{TriState, TriStateParser};
+///
+/// let parser = TriStateParser::new()
+///     .word("enabled", TriState::True)
+///     .word("disabled", TriState::False)
+///     .word("unset", TriState::Default)
+///     .case_insensitive(true);
+///
+/// assert_eq!(parser.parse("Enabled"), Ok(TriState::True));
+/// assert_eq!(parser.parse("unset"), Ok(TriState::Default));
+/// assert_eq!(parser.parse("yes").unwrap_err().accepted(), ["enabled", "disabled", "unset"]);
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct TriStateParser {
+    words: Vec<(String, TriState)>,
+    case_insensitive: bool
+}
+
+impl TriStateParser {
+    /// Returns a case-sensitive parser that accepts no words
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a parser for the vocabulary accepted by `TriState::from_str`, ignoring ASCII case
+    ///
+    /// ```rust
+    /// use tristate::{TriState, TriStateParser};
+    ///
+    /// let parser = TriStateParser::standard().word("enabled", TriState::True);
+    /// assert_eq!(parser.parse("ENABLED"), Ok(TriState::True));
+    /// assert_eq!(parser.parse("auto"), Ok(TriState::Default));
+    /// ```
+    pub fn standard() -> Self {
+        STANDARD.iter()
+            .fold(Self::new(), |parser, &(word, value)| parser.word(word, value))
+            .case_insensitive(true)
+    }
+
+    /// Returns the parser with an additional word that parses to `value`
+    ///
+    /// When a word is added more than once, its first value is used.
+    pub fn word<S: Into<String>>(mut self, word: S, value: TriState) -> Self {
+        self.words.push((word.into(), value));
+        self
+    }
+
+    /// Returns the parser with additional words that all parse to `value`
+    pub fn words<I: IntoIterator<Item = S>, S: Into<String>>(self, words: I, value: TriState) -> Self {
+        words.into_iter().fold(self, |parser, word| parser.word(word, value))
+    }
+
+    /// Returns the parser set to ignore or respect ASCII case when matching words
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Parses a tri-state from one of the parser's words
+    pub fn parse(&self, s: &str) -> Result<TriState, ParseTriStateError> {
+        self.words.iter()
+            .find(|(word, _)| if self.case_insensitive { word.eq_ignore_ascii_case(s) } else { word == s })
+            .map(|&(_, value)| value)
+            .ok_or_else(|| ParseTriStateError {
+                input: s.to_owned(),
+                accepted: self.words.iter().map(|(word, _)| word.clone()).collect()
+            })
+    }
+}
+
+/// The error returned when text is not one of the accepted words for a tri-state
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ParseTriStateError {
+    input: String,
+    accepted: Vec<String>
+}
+
+impl ParseTriStateError {
+    /// Returns the text that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the words that would have been accepted
+    pub fn accepted(&self) -> &[String] {
+        &self.accepted
+    }
+}
+
+impl Display for ParseTriStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "invalid tri-state {:?}, expected one of: ", self.input)?;
+        for (i, word) in self.accepted.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if word.is_empty() { f.write_str("\"\"")? } else { f.write_str(word)? }
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseTriStateError {}
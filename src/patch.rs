@@ -0,0 +1,254 @@
+//! A three-state type for partial updates of any value.
+//!
+//! `TriState` distinguishes a definite boolean from a fallback to the default. A [`Patch`]
+//! generalises that to any type when describing an update: the field can be left unchanged,
+//! cleared, or set to a new value.
+//!
+//! With serde, a missing field deserializes as `Patch::Absent` and an explicit `null` as
+//! `Patch::Null`, which plain `Option` cannot tell apart. The field needs `#[serde(default)]`
+//! for the missing case, and usually `skip_serializing_if = "Patch::is_absent"`:
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")]
+//! # fn main() {
+//! use serde::{Deserialize, Serialize};
+//! use tristate::Patch;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct UserPatch {
+//!     #[serde(default, skip_serializing_if = "Patch::is_absent")]
+//!     nickname: Patch<String>,
+//!     #[serde(default, skip_serializing_if = "Patch::is_absent")]
+//!     age: Patch<u32>
+//! }
+//!
+//! let patch: UserPatch = serde_json::from_str(r#"{"nickname":null}"#).unwrap();
+//! assert_eq!(patch.nickname, Patch::Null);
+//! assert_eq!(patch.age, Patch::Absent);
+//!
+//! let mut nickname = Some("Will".to_owned());
+//! let mut age = Some(30);
+//! patch.nickname.apply(&mut nickname);
+//! patch.age.apply(&mut age);
+//! assert_eq!((nickname, age), (None, Some(30)));
+//!
+//! let patch = UserPatch { nickname: Patch::Absent, age: Patch::Value(31) };
+//! assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"age":31}"#);
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "serde")]
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::TriState;
+
+/// An update to an optional value, which either leaves it unchanged, clears it, or sets it
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Patch<T> {
+    /// Leaves the value unchanged
+    #[default]
+    Absent,
+    /// Clears the value
+    Null,
+    /// Sets the value
+    Value(T)
+}
+
+impl<T> Patch<T> {
+    /// Returns true if the patch leaves the value unchanged
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Self::Absent)
+    }
+
+    /// Returns true if the patch clears the value
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns true if the patch sets the value
+    pub fn is_value(&self) -> bool {
+        matches!(self, Self::Value(_))
+    }
+
+    /// Applies the patch to an optional value
+    ///
+    /// ```rust
+    /// use tristate::Patch;
+    ///
+    /// let mut value = Some(1);
+    /// Patch::Absent.apply(&mut value);
+    /// assert_eq!(value, Some(1));
+    /// Patch::Value(2).apply(&mut value);
+    /// assert_eq!(value, Some(2));
+    /// Patch::Null.apply(&mut value);
+    /// assert_eq!(value, None);
+    /// ```
+    pub fn apply(self, target: &mut Option<T>) {
+        match self {
+            Self::Absent => {}
+            Self::Null => *target = None,
+            Self::Value(value) => *target = Some(value)
+        }
+    }
+
+    /// Returns the patch that applies `self` followed by `later`
+    ///
+    /// ```rust
+    /// use tristate::Patch;
+    ///
+    /// assert_eq!(Patch::Value(1).then(Patch::Absent), Patch::Value(1));
+    /// assert_eq!(Patch::Value(1).then(Patch::Null), Patch::Null);
+    /// assert_eq!(Patch::Null.then(Patch::Value(2)), Patch::Value(2));
+    /// ```
+    pub fn then(self, later: Patch<T>) -> Patch<T> {
+        if later.is_absent() { self } else { later }
+    }
+
+    /// Returns this patch, or `other` if this patch leaves the value unchanged
+    pub fn or(self, other: Patch<T>) -> Patch<T> {
+        if self.is_absent() { other } else { self }
+    }
+
+    /// Returns the value the patch sets, if any
+    pub fn value(self) -> Option<T> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None
+        }
+    }
+
+    /// Returns the patch as an `Option<Option<T>>`, where `None` leaves the value unchanged
+    /// and `Some(None)` clears it
+    pub fn into_option(self) -> Option<Option<T>> {
+        match self {
+            Self::Absent => None,
+            Self::Null => Some(None),
+            Self::Value(value) => Some(Some(value))
+        }
+    }
+
+    /// Returns a patch borrowing the value
+    pub fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Self::Absent => Patch::Absent,
+            Self::Null => Patch::Null,
+            Self::Value(value) => Patch::Value(value)
+        }
+    }
+
+    /// Returns a patch mutably borrowing the value
+    pub fn as_mut(&mut self) -> Patch<&mut T> {
+        match self {
+            Self::Absent => Patch::Absent,
+            Self::Null => Patch::Null,
+            Self::Value(value) => Patch::Value(value)
+        }
+    }
+
+    /// Returns the patch with the value mapped by `f`
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Patch<U> {
+        match self {
+            Self::Absent => Patch::Absent,
+            Self::Null => Patch::Null,
+            Self::Value(value) => Patch::Value(f(value))
+        }
+    }
+
+    /// Returns the patch produced by `f` from the value, if the patch sets one
+    pub fn and_then<U, F: FnOnce(T) -> Patch<U>>(self, f: F) -> Patch<U> {
+        match self {
+            Self::Absent => Patch::Absent,
+            Self::Null => Patch::Null,
+            Self::Value(value) => f(value)
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    /// Returns the patch that sets the value, or clears it for `None`
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Self::Value)
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Patch<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        value.map_or(Self::Absent, Self::from)
+    }
+}
+
+impl From<TriState> for Patch<bool> {
+    /// Returns the patch for a tri-state, where `TriState::Default` leaves the value unchanged
+    ///
+    /// ```rust
+    /// use tristate::{Patch, TriState};
+    ///
+    /// assert_eq!(Patch::from(TriState::True), Patch::Value(true));
+    /// assert_eq!(Patch::from(TriState::Default), Patch::Absent);
+    /// ```
+    fn from(value: TriState) -> Self {
+        Option::<bool>::from(value).map_or(Self::Absent, Self::Value)
+    }
+}
+
+impl TryFrom<Patch<bool>> for TriState {
+    type Error = NullPatchError;
+
+    /// Returns the tri-state for a patch, which fails for `Patch::Null` as no tri-state clears
+    /// a value
+    ///
+    /// ```rust
+    /// use std::convert::TryFrom;
+    /// use tristate::{Patch, TriState};
+    ///
+    /// assert_eq!(TriState::try_from(Patch::Value(false)), Ok(TriState::False));
+    /// assert_eq!(TriState::try_from(Patch::Absent), Ok(TriState::Default));
+    /// assert!(TriState::try_from(Patch::Null).is_err());
+    /// ```
+    fn try_from(patch: Patch<bool>) -> Result<Self, Self::Error> {
+        match patch {
+            Patch::Absent => Ok(TriState::Default),
+            Patch::Null => Err(NullPatchError(())),
+            Patch::Value(value) => Ok(TriState::from(value))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Patch<T> {
+    /// Serializes the patch as an optional value, where both `Patch::Absent` and `Patch::Null`
+    /// become `null` unless absent fields are skipped
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value(value) => serializer.serialize_some(value),
+            _ => serializer.serialize_none()
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    /// Deserializes `null` as `Patch::Null` and any other value as `Patch::Value`, leaving
+    /// missing fields to `#[serde(default)]`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// The error returned when converting a `Patch::Null` into a `TriState`
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct NullPatchError(());
+
+impl Display for NullPatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str("a null patch has no corresponding tri-state")
+    }
+}
+
+impl Error for NullPatchError {}
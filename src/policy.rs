@@ -0,0 +1,363 @@
+//! Access decisions combined from rules.
+//!
+//! A [`Rule`] decides on a context with a tri-state, where `TriState::True` permits,
+//! `TriState::False` denies and `TriState::Default` does not apply, or fails with an
+//! [`Indeterminate`] error when it cannot decide. A [`Policy`] combines rules with one of the
+//! [`Combining`] algorithms of XACML, and can [`trace`](Policy::trace) how it reached a decision.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::policy::{Combining, Policy};
+//!
+//! struct Request {
+//!     user: &'static str,
+//!     owner: &'static str,
+//!     banned: bool
+//! }
+//!
+//! let policy = Policy::new(Combining::DenyOverrides)
+//!     .rule("owner", |r: &Request| if r.user == r.owner { TriState::True } else { TriState::Default })
+//!     .rule("banned", |r: &Request| if r.banned { TriState::False } else { TriState::Default })
+//!     .fallback(TriState::False);
+//!
+//! assert_eq!(policy.evaluate(&Request { user: "ann", owner: "ann", banned: false }), Ok(TriState::True));
+//! assert_eq!(policy.evaluate(&Request { user: "ann", owner: "ann", banned: true }), Ok(TriState::False));
+//! assert_eq!(policy.evaluate(&Request { user: "bob", owner: "ann", banned: false }), Ok(TriState::False));
+//!
+//! let trace = policy.trace(&Request { user: "ann", owner: "ann", banned: true });
+//! assert_eq!(trace.to_string(), "\
+//! deny-overrides
+//!   owner: permit
+//!   banned: deny (decisive)
+//! result: deny
+//! ");
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+use crate::TriState;
+
+/// A rule that permits, denies or does not apply to a context
+///
+/// Any `Fn(&Ctx) -> TriState` is a rule that always decides, and [`Fallible`] turns a function
+/// returning a `Result` into a rule. A [`Policy`] is itself a rule, so policies can be nested.
+pub trait Rule<Ctx: ?Sized> {
+    /// Returns `TriState::True` to permit, `TriState::False` to deny and `TriState::Default`
+    /// if the rule does not apply, or an error if the rule cannot decide
+    fn evaluate(&self, ctx: &Ctx) -> Result<TriState, Indeterminate>;
+}
+
+impl<Ctx: ?Sized, F: Fn(&Ctx) -> TriState> Rule<Ctx> for F {
+    fn evaluate(&self, ctx: &Ctx) -> Result<TriState, Indeterminate> {
+        Ok(self(ctx))
+    }
+}
+
+/// A rule made from a function that can fail to decide
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::policy::{Combining, Fallible, Indeterminate, Policy};
+///
+/// let policy = Policy::new(Combining::PermitOverrides)
+///     .rule("parse", Fallible(|input: &str| match input.parse::<u32>() {
+///         Ok(age) if age >= 18 => Ok(TriState::True),
+///         Ok(_) => Ok(TriState::False),
+///         Err(_) => Err(Indeterminate::new("age is not a number"))
+///     }));
+///
+/// assert_eq!(policy.evaluate("21"), Ok(TriState::True));
+/// assert_eq!(policy.evaluate("twelve").unwrap_err().reason(), "age is not a number");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Fallible<F>(pub F);
+
+impl<Ctx: ?Sized, F: Fn(&Ctx) -> Result<TriState, Indeterminate>> Rule<Ctx> for Fallible<F> {
+    fn evaluate(&self, ctx: &Ctx) -> Result<TriState, Indeterminate> {
+        (self.0)(ctx)
+    }
+}
+
+/// The error returned when a rule or policy cannot reach a decision
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Indeterminate {
+    reason: String
+}
+
+impl Indeterminate {
+    /// Returns an error for the given reason
+    pub fn new<S: Into<String>>(reason: S) -> Self {
+        Self { reason: reason.into() }
+    }
+
+    /// Returns the reason no decision was reached
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Display for Indeterminate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "indeterminate: {}", self.reason)
+    }
+}
+
+impl Error for Indeterminate {}
+
+/// The algorithm a [`Policy`] combines the decisions of its rules with
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Combining {
+    /// Any rule that denies decides, otherwise an error is indeterminate, and otherwise any rule
+    /// that permits decides
+    DenyOverrides,
+    /// Any rule that permits decides, otherwise an error is indeterminate, and otherwise any rule
+    /// that denies decides
+    PermitOverrides,
+    /// The first rule that applies decides, and an error before it is indeterminate
+    FirstApplicable,
+    /// The only rule that applies decides, and an error or more than one rule applying is
+    /// indeterminate
+    OnlyOneApplicable
+}
+
+impl Display for Combining {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str(match self {
+            Self::DenyOverrides => "deny-overrides",
+            Self::PermitOverrides => "permit-overrides",
+            Self::FirstApplicable => "first-applicable",
+            Self::OnlyOneApplicable => "only-one-applicable"
+        })
+    }
+}
+
+type BoxedRule<Ctx> = Box<dyn Rule<Ctx> + Send + Sync>;
+
+/// Named rules combined into a single decision
+///
+/// When no rule applies the policy does not apply either, unless it is given a
+/// [`fallback`](Policy::fallback) decision.
+pub struct Policy<Ctx: ?Sized> {
+    combining: Combining,
+    rules: Vec<(String, BoxedRule<Ctx>)>,
+    fallback: TriState
+}
+
+impl<Ctx: ?Sized> Policy<Ctx> {
+    /// Returns a policy without rules that combines with the given algorithm
+    pub fn new(combining: Combining) -> Self {
+        Self { combining, rules: Vec::new(), fallback: TriState::Default }
+    }
+
+    /// Returns the policy with a named rule added after the others
+    pub fn rule<S: Into<String>, R: Rule<Ctx> + Send + Sync + 'static>(mut self, name: S, rule: R) -> Self {
+        self.rules.push((name.into(), Box::new(rule)));
+        self
+    }
+
+    /// Returns the policy with the decision to make when no rule applies
+    pub fn fallback(mut self, decision: TriState) -> Self {
+        self.fallback = decision;
+        self
+    }
+
+    /// Returns the combining algorithm
+    pub fn combining(&self) -> Combining {
+        self.combining
+    }
+
+    /// Returns the names of the rules in order
+    pub fn rule_names(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the decision for a context
+    pub fn evaluate(&self, ctx: &Ctx) -> Result<TriState, Indeterminate> {
+        self.trace(ctx).result
+    }
+
+    /// Returns the decision for a context together with the decisions of the rules that were
+    /// evaluated to reach it
+    ///
+    /// Rules are evaluated in order, and the rules after the one that decides are skipped
+    /// unless the algorithm needs them.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::policy::{Combining, Policy};
+    ///
+    /// let policy = Policy::new(Combining::OnlyOneApplicable)
+    ///     .rule("admin", |user: &str| if user == "root" { TriState::True } else { TriState::Default })
+    ///     .rule("guest", |user: &str| if user.starts_with("guest") { TriState::False } else { TriState::Default })
+    ///     .rule("named", |user: &str| if user.starts_with('r') { TriState::True } else { TriState::Default });
+    ///
+    /// let trace = policy.trace("guest42");
+    /// assert_eq!(trace.result(), &Ok(TriState::False));
+    /// assert_eq!(trace.decisive().map(|step| step.rule()), Some("guest"));
+    ///
+    /// let trace = policy.trace("root");
+    /// assert_eq!(trace.result().as_ref().unwrap_err().reason(), "more than one rule applies: admin, named");
+    /// assert_eq!(trace.decisive(), None);
+    ///
+    /// let trace = policy.trace("alice");
+    /// assert_eq!(trace.result(), &Ok(TriState::Default));
+    /// assert_eq!(trace.steps().len(), 3);
+    /// ```
+    pub fn trace(&self, ctx: &Ctx) -> Trace<'_> {
+        let mut steps = Vec::new();
+        let mut decisive = None;
+        let mut error = None;
+        for (name, rule) in &self.rules {
+            let result = rule.evaluate(ctx);
+            let index = steps.len();
+            match (&result, self.combining) {
+                (Ok(TriState::False), Combining::DenyOverrides) | (Ok(TriState::True), Combining::PermitOverrides) => {
+                    decisive = Some(index);
+                    error = None;
+                    steps.push(Step { rule: name, result });
+                    break;
+                }
+                (Ok(TriState::Default), _) => {}
+                (Ok(_), Combining::DenyOverrides) | (Ok(_), Combining::PermitOverrides) => {
+                    decisive = decisive.or(Some(index));
+                }
+                (Ok(_), Combining::FirstApplicable) | (Err(_), Combining::FirstApplicable) => {
+                    decisive = Some(index);
+                    steps.push(Step { rule: name, result });
+                    break;
+                }
+                (Ok(_), Combining::OnlyOneApplicable) => {
+                    if decisive.is_some() {
+                        error = error.or(Some(index));
+                    }
+                    decisive = decisive.or(Some(index));
+                }
+                (Err(_), _) => error = error.or(Some(index))
+            }
+            steps.push(Step { rule: name, result });
+        }
+
+        let (result, decisive) = match (error, decisive) {
+            (Some(index), Some(first)) if self.combining == Combining::OnlyOneApplicable && steps[index].result.is_ok() => {
+                let names: Vec<&str> = steps[first..].iter()
+                    .filter(|step| matches!(step.result, Ok(TriState::True) | Ok(TriState::False)))
+                    .map(|step| step.rule)
+                    .collect();
+                (Err(Indeterminate::new(alloc::format!("more than one rule applies: {}", names.join(", ")))), None)
+            }
+            (Some(index), _) => (steps[index].result.clone(), Some(index)),
+            (None, Some(index)) => (steps[index].result.clone(), Some(index)),
+            (None, None) => (Ok(TriState::Default), None)
+        };
+        let fallback = result == Ok(TriState::Default) && self.fallback != TriState::Default;
+        Trace {
+            combining: self.combining,
+            result: if fallback { Ok(self.fallback) } else { result },
+            steps,
+            decisive,
+            fallback
+        }
+    }
+}
+
+impl<Ctx: ?Sized> Rule<Ctx> for Policy<Ctx> {
+    fn evaluate(&self, ctx: &Ctx) -> Result<TriState, Indeterminate> {
+        Policy::evaluate(self, ctx)
+    }
+}
+
+impl<Ctx: ?Sized> core::fmt::Debug for Policy<Ctx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Policy")
+            .field("combining", &self.combining)
+            .field("rules", &self.rule_names().collect::<Vec<_>>())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+/// The decision of a rule evaluated by a [`Policy`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Step<'a> {
+    rule: &'a str,
+    result: Result<TriState, Indeterminate>
+}
+
+impl<'a> Step<'a> {
+    /// Returns the name of the rule
+    pub fn rule(&self) -> &'a str {
+        self.rule
+    }
+
+    /// Returns the decision of the rule
+    pub fn result(&self) -> &Result<TriState, Indeterminate> {
+        &self.result
+    }
+}
+
+/// How a [`Policy`] reached a decision
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Trace<'a> {
+    combining: Combining,
+    steps: Vec<Step<'a>>,
+    decisive: Option<usize>,
+    result: Result<TriState, Indeterminate>,
+    fallback: bool
+}
+
+impl<'a> Trace<'a> {
+    /// Returns the decision of the policy
+    pub fn result(&self) -> &Result<TriState, Indeterminate> {
+        &self.result
+    }
+
+    /// Returns the decisions of the rules that were evaluated, in order
+    pub fn steps(&self) -> &[Step<'a>] {
+        &self.steps
+    }
+
+    /// Returns the rule whose decision the policy took, if any
+    pub fn decisive(&self) -> Option<&Step<'a>> {
+        self.decisive.map(|index| &self.steps[index])
+    }
+
+    /// Returns true if no rule applied and the decision is the fallback of the policy
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+}
+
+impl Display for Trace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        writeln!(f, "{}", self.combining)?;
+        for (index, step) in self.steps.iter().enumerate() {
+            write!(f, "  {}: {}", step.rule, DisplayResult(&step.result))?;
+            if self.decisive == Some(index) {
+                f.write_str(" (decisive)")?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "result: {}", DisplayResult(&self.result))?;
+        if self.fallback {
+            f.write_str(" (fallback)")?;
+        }
+        writeln!(f)
+    }
+}
+
+struct DisplayResult<'a>(&'a Result<TriState, Indeterminate>);
+
+impl Display for DisplayResult<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self.0 {
+            Ok(TriState::True) => f.write_str("permit"),
+            Ok(TriState::False) => f.write_str("deny"),
+            Ok(TriState::Default) => f.write_str("not applicable"),
+            Err(error) => Display::fmt(error, f)
+        }
+    }
+}
@@ -0,0 +1,810 @@
+//! Boolean satisfiability over partial assignments.
+//!
+//! A partial assignment of boolean variables is a slice of `TriState`, where `TriState::Default`
+//! leaves a variable unassigned. Under strong Kleene logic a clause then evaluates to
+//! `TriState::True` once any literal is true, to `TriState::False` once every literal is false,
+//! and to `TriState::Default` while it is still undecided.
+//!
+//! A [`Cnf`] stores a formula in conjunctive normal form, reads and writes the DIMACS format,
+//! and is solved with conflict-driven clause learning over two watched literals per clause.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::sat::{Cnf, Lit, Solution};
+//!
+//! // (x0 | x1) & (!x0 | x1) & (!x1 | x2)
+//! let mut cnf = Cnf::new();
+//! cnf.add_clause([Lit::positive(0), Lit::positive(1)]);
+//! cnf.add_clause([Lit::negative(0), Lit::positive(1)]);
+//! cnf.add_clause([Lit::negative(1), Lit::positive(2)]);
+//!
+//! let partial = [TriState::True, TriState::Default, TriState::Default];
+//! assert_eq!(cnf.eval(&partial), TriState::Default);
+//! assert_eq!(cnf.propagate(&partial), Ok(vec![TriState::True; 3]));
+//!
+//! match cnf.solve() {
+//!     Solution::Sat(model) => assert_eq!(cnf.eval(&model), TriState::True),
+//!     Solution::Unsat => unreachable!()
+//! }
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::ops::Not;
+use core::str::FromStr;
+
+use crate::TriState;
+
+/// A variable or its negation
+///
+/// Variables are numbered from zero, while the DIMACS format numbers them from one.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Lit(usize);
+
+impl Lit {
+    /// The largest variable a literal can hold, chosen so that both the literal and its DIMACS
+    /// integer fit
+    pub const MAX_VAR: usize = (usize::MAX >> 1) - 1;
+
+    /// Returns the literal that is true when the variable is
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is greater than [`Lit::MAX_VAR`].
+    pub const fn positive(var: usize) -> Self {
+        assert!(var <= Self::MAX_VAR, "variable out of range");
+        Self(var << 1)
+    }
+
+    /// Returns the literal that is true when the variable is false
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is greater than [`Lit::MAX_VAR`].
+    pub const fn negative(var: usize) -> Self {
+        assert!(var <= Self::MAX_VAR, "variable out of range");
+        Self(var << 1 | 1)
+    }
+
+    /// Returns the literal for a DIMACS integer, or `None` for the clause terminator `0` and for
+    /// a variable greater than [`Lit::MAX_VAR`]
+    ///
+    /// ```rust
+    /// use tristate::sat::Lit;
+    ///
+    /// assert_eq!(Lit::from_dimacs(-3), Some(Lit::negative(2)));
+    /// assert_eq!(Lit::from_dimacs(0), None);
+    /// assert_eq!(Lit::from_dimacs(i64::MIN), None);
+    /// assert_eq!(Lit::positive(0).to_dimacs(), 1);
+    /// ```
+    pub fn from_dimacs(value: i64) -> Option<Self> {
+        let var = usize::try_from(value.unsigned_abs()).ok()?.checked_sub(1)?;
+        if var > Self::MAX_VAR {
+            return None;
+        }
+        Some(if value < 0 { Self::negative(var) } else { Self::positive(var) })
+    }
+
+    /// Returns the DIMACS integer for the literal
+    pub fn to_dimacs(self) -> i64 {
+        let var = self.var() as i64 + 1;
+        if self.is_negative() { -var } else { var }
+    }
+
+    /// Returns the variable of the literal
+    pub const fn var(self) -> usize {
+        self.0 >> 1
+    }
+
+    /// Returns true if the literal is the negation of its variable
+    pub const fn is_negative(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// Returns the value of the literal under a partial assignment, where variables beyond the
+    /// end of the assignment are unassigned
+    pub fn eval(self, assignment: &[TriState]) -> TriState {
+        let value = assignment.get(self.var()).copied().unwrap_or_default();
+        if self.is_negative() { !value } else { value }
+    }
+}
+
+impl Not for Lit {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0 ^ 1)
+    }
+}
+
+impl Display for Lit {
+    /// Formats the literal as a DIMACS integer
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "{}", self.to_dimacs())
+    }
+}
+
+/// Returns the value of a clause under a partial assignment
+///
+/// ```rust
+/// use tristate::TriState;
+/// use tristate::sat::{eval_clause, Lit};
+///
+/// let clause = [Lit::positive(0), Lit::negative(1)];
+/// assert_eq!(eval_clause(&clause, &[TriState::False, TriState::False]), TriState::True);
+/// assert_eq!(eval_clause(&clause, &[TriState::False, TriState::Default]), TriState::Default);
+/// assert_eq!(eval_clause(&clause, &[TriState::False, TriState::True]), TriState::False);
+/// assert_eq!(eval_clause(&[], &[]), TriState::False);
+/// ```
+pub fn eval_clause(clause: &[Lit], assignment: &[TriState]) -> TriState {
+    clause.iter().fold(TriState::False, |value, lit| value | lit.eval(assignment))
+}
+
+/// A formula in conjunctive normal form, stored as a list of clauses over numbered variables
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Cnf {
+    num_vars: usize,
+    lits: Vec<Lit>,
+    ends: Vec<usize>
+}
+
+impl Cnf {
+    /// Returns a formula without any variables or clauses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a formula over `num_vars` variables without any clauses
+    pub fn with_vars(num_vars: usize) -> Self {
+        Self { num_vars, ..Self::default() }
+    }
+
+    /// Returns the number of variables, which covers every variable used by a clause
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Returns the number of clauses
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns true if the formula has no clauses
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Adds a clause, which is the disjunction of its literals
+    pub fn add_clause<I: IntoIterator<Item = Lit>>(&mut self, clause: I) {
+        let start = self.lits.len();
+        self.lits.extend(clause);
+        for lit in &self.lits[start..] {
+            self.num_vars = self.num_vars.max(lit.var() + 1);
+        }
+        self.ends.push(self.lits.len());
+    }
+
+    /// Returns the clause at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn clause(&self, index: usize) -> &[Lit] {
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        &self.lits[start..self.ends[index]]
+    }
+
+    /// Returns an iterator over the clauses
+    pub fn clauses(&self) -> impl Iterator<Item = &[Lit]> {
+        (0..self.len()).map(move |index| self.clause(index))
+    }
+
+    /// Returns the value of the formula under a partial assignment
+    pub fn eval(&self, assignment: &[TriState]) -> TriState {
+        self.clauses().fold(TriState::True, |value, clause| value & eval_clause(clause, assignment))
+    }
+
+    /// Extends a partial assignment with every literal implied by unit propagation, or returns
+    /// the index of a clause that the assignment falsifies
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::sat::{Cnf, Lit};
+    ///
+    /// let mut cnf = Cnf::new();
+    /// cnf.add_clause([Lit::negative(0), Lit::positive(1)]);
+    /// cnf.add_clause([Lit::negative(1)]);
+    ///
+    /// assert_eq!(cnf.propagate(&[]), Ok(vec![TriState::False, TriState::False]));
+    /// assert_eq!(cnf.propagate(&[TriState::True]), Err(0));
+    /// ```
+    pub fn propagate(&self, assignment: &[TriState]) -> Result<Vec<TriState>, usize> {
+        let mut solver = Solver::new(self, assignment)?;
+        match solver.propagate() {
+            Some(conflict) => Err(conflict),
+            None => Ok(solver.values)
+        }
+    }
+
+    /// Searches for an assignment of every variable that satisfies the formula
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::sat::{Cnf, Lit, Solution};
+    ///
+    /// // Pigeons in holes, one pigeon each
+    /// fn pigeonhole(pigeons: usize, holes: usize) -> Cnf {
+    ///     let var = |pigeon: usize, hole: usize| pigeon * holes + hole;
+    ///     let mut cnf = Cnf::new();
+    ///     for pigeon in 0..pigeons {
+    ///         cnf.add_clause((0..holes).map(|hole| Lit::positive(var(pigeon, hole))));
+    ///     }
+    ///     for hole in 0..holes {
+    ///         for a in 0..pigeons {
+    ///             for b in a + 1..pigeons {
+    ///                 cnf.add_clause([Lit::negative(var(a, hole)), Lit::negative(var(b, hole))]);
+    ///             }
+    ///         }
+    ///     }
+    ///     cnf
+    /// }
+    ///
+    /// assert_eq!(pigeonhole(5, 4).solve(), Solution::Unsat);
+    ///
+    /// let cnf = pigeonhole(5, 5);
+    /// let model = cnf.solve().model().unwrap();
+    /// assert_eq!(cnf.eval(&model), TriState::True);
+    /// assert_eq!(model.iter().filter(|&&value| value == TriState::True).count(), 5);
+    /// ```
+    pub fn solve(&self) -> Solution {
+        self.solve_under(&[])
+    }
+
+    /// Searches for an assignment that satisfies the formula and agrees with every definite
+    /// value of a partial assignment
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::sat::{Cnf, Lit, Solution};
+    ///
+    /// let mut cnf = Cnf::new();
+    /// cnf.add_clause([Lit::negative(0), Lit::negative(1)]);
+    ///
+    /// assert_eq!(cnf.solve_under(&[TriState::True]), Solution::Sat(vec![TriState::True, TriState::False]));
+    /// assert_eq!(cnf.solve_under(&[TriState::True, TriState::True]), Solution::Unsat);
+    /// ```
+    pub fn solve_under(&self, assumptions: &[TriState]) -> Solution {
+        match Solver::new(self, assumptions) {
+            Ok(mut solver) => solver.search(),
+            Err(_) => Solution::Unsat
+        }
+    }
+}
+
+impl Display for Cnf {
+    /// Writes the formula in the DIMACS CNF format
+    ///
+    /// ```rust
+    /// use tristate::sat::{Cnf, Lit};
+    ///
+    /// let mut cnf = Cnf::with_vars(3);
+    /// cnf.add_clause([Lit::positive(0), Lit::negative(2)]);
+    /// cnf.add_clause([Lit::positive(1)]);
+    ///
+    /// assert_eq!(cnf.to_string(), "p cnf 3 2\n1 -3 0\n2 0\n");
+    /// assert_eq!(cnf.to_string().parse(), Ok(cnf));
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        writeln!(f, "p cnf {} {}", self.num_vars, self.len())?;
+        for clause in self.clauses() {
+            for lit in clause {
+                write!(f, "{} ", lit)?;
+            }
+            f.write_str("0\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cnf {
+    type Err = ParseDimacsError;
+
+    /// Parses a formula in the DIMACS CNF format
+    ///
+    /// Comment lines start with `c`, and a line starting with `%` ends the formula as in the
+    /// SATLIB benchmarks. Clauses may span lines, and must not use variables beyond those
+    /// declared in the header.
+    ///
+    /// ```rust
+    /// use tristate::sat::{Cnf, ParseDimacsError};
+    ///
+    /// let cnf: Cnf = "c example\np cnf 2 2\n1 -2\n0 2 0\n".parse().unwrap();
+    /// assert_eq!(cnf.len(), 2);
+    /// assert_eq!("p cnf 1 1\n2 0\n".parse::<Cnf>(), Err(ParseDimacsError::VariableOutOfRange { line: 2, var: 2 }));
+    /// assert_eq!("p cnf 1 1\n-9223372036854775808 0\n".parse::<Cnf>(), Err(ParseDimacsError::Overflow { line: 2 }));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cnf = None;
+        let mut expected = 0;
+        let mut clause = Vec::new();
+        for (index, text) in s.lines().enumerate() {
+            let line = index + 1;
+            let text = text.trim();
+            if text.starts_with('%') {
+                break;
+            }
+            if text.is_empty() || text.starts_with('c') {
+                continue;
+            }
+            let cnf = match &mut cnf {
+                Some(cnf) => cnf,
+                None => {
+                    let mut fields = text.split_whitespace();
+                    let (vars, clauses) = match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+                        (Some("p"), Some("cnf"), Some(vars), Some(clauses), None) => (vars.parse(), clauses.parse()),
+                        _ => return Err(ParseDimacsError::InvalidHeader { line })
+                    };
+                    match (vars, clauses) {
+                        (Ok(vars), Ok(_)) if vars > Lit::MAX_VAR + 1 => return Err(ParseDimacsError::Overflow { line }),
+                        (Ok(vars), Ok(clauses)) => {
+                            expected = clauses;
+                            cnf = Some(Cnf::with_vars(vars));
+                        }
+                        _ => return Err(ParseDimacsError::InvalidHeader { line })
+                    }
+                    continue;
+                }
+            };
+            for field in text.split_whitespace() {
+                let value: i64 = field.parse().map_err(|_| ParseDimacsError::InvalidLiteral { line })?;
+                if value == 0 {
+                    cnf.add_clause(clause.drain(..));
+                    continue;
+                }
+                match Lit::from_dimacs(value) {
+                    Some(lit) if lit.var() < cnf.num_vars => clause.push(lit),
+                    Some(lit) => return Err(ParseDimacsError::VariableOutOfRange { line, var: lit.var() + 1 }),
+                    None => return Err(ParseDimacsError::Overflow { line })
+                }
+            }
+        }
+        let cnf = cnf.ok_or(ParseDimacsError::MissingHeader)?;
+        if !clause.is_empty() {
+            return Err(ParseDimacsError::UnterminatedClause);
+        }
+        if cnf.len() != expected {
+            return Err(ParseDimacsError::ClauseCount { expected, found: cnf.len() });
+        }
+        Ok(cnf)
+    }
+}
+
+/// The outcome of searching for a satisfying assignment
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Solution {
+    /// The formula is satisfied by the assignment, which gives every variable a definite value
+    Sat(Vec<TriState>),
+    /// No assignment satisfies the formula
+    Unsat
+}
+
+impl Solution {
+    /// Returns true if a satisfying assignment was found
+    pub fn is_sat(&self) -> bool {
+        matches!(self, Self::Sat(_))
+    }
+
+    /// Returns the satisfying assignment, if any
+    pub fn model(self) -> Option<Vec<TriState>> {
+        match self {
+            Self::Sat(model) => Some(model),
+            Self::Unsat => None
+        }
+    }
+}
+
+/// The error returned when parsing a formula in the DIMACS format fails
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParseDimacsError {
+    /// The input has no `p cnf` header
+    MissingHeader,
+    /// The header is not of the form `p cnf <variables> <clauses>`
+    InvalidHeader {
+        /// Line number of the header, counting from one
+        line: usize
+    },
+    /// A literal is not an integer
+    InvalidLiteral {
+        /// Line number of the literal, counting from one
+        line: usize
+    },
+    /// A literal uses a variable beyond those declared in the header
+    VariableOutOfRange {
+        /// Line number of the literal, counting from one
+        line: usize,
+        /// The variable, counting from one
+        var: usize
+    },
+    /// A literal or the number of variables in the header is too large for a [`Lit`]
+    Overflow {
+        /// Line number of the literal or header, counting from one
+        line: usize
+    },
+    /// The last clause is missing its terminating `0`
+    UnterminatedClause,
+    /// The number of clauses differs from the header
+    ClauseCount {
+        /// Number of clauses declared in the header
+        expected: usize,
+        /// Number of clauses found
+        found: usize
+    }
+}
+
+impl Display for ParseDimacsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::MissingHeader => f.write_str("missing DIMACS header"),
+            Self::InvalidHeader { line } => write!(f, "invalid DIMACS header on line {}", line),
+            Self::InvalidLiteral { line } => write!(f, "invalid literal on line {}", line),
+            Self::VariableOutOfRange { line, var } => {
+                write!(f, "variable {} on line {} is not declared in the header", var, line)
+            }
+            Self::Overflow { line } => write!(f, "variable on line {} is too large", line),
+            Self::UnterminatedClause => f.write_str("last clause is not terminated by 0"),
+            Self::ClauseCount { expected, found } => {
+                write!(f, "expected {} clauses but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for ParseDimacsError {}
+
+/// Factor by which the activity increment grows after each conflict
+const ACTIVITY_GROWTH: f64 = 1.0 / 0.95;
+/// Activity above which every activity is scaled down to avoid overflow
+const ACTIVITY_LIMIT: f64 = 1e100;
+
+/// A conflict-driven clause learning solver for a single search
+///
+/// Every clause of two or more literals watches its first two literals, and the implied
+/// literal of a reason clause is kept first.
+struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<TriState>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_limits: Vec<usize>,
+    head: usize,
+    activity: Vec<f64>,
+    increment: f64,
+    order: Order,
+    phases: Vec<bool>
+}
+
+impl Solver {
+    /// Returns a solver with the unit clauses and assumptions assigned at level zero, or the
+    /// index of a clause they falsify
+    fn new(cnf: &Cnf, assumptions: &[TriState]) -> Result<Self, usize> {
+        let num_vars = cnf.num_vars.max(assumptions.len());
+        let mut solver = Self {
+            clauses: cnf.clauses().map(<[Lit]>::to_vec).collect(),
+            watches: vec![Vec::new(); 2 * num_vars],
+            values: vec![TriState::Default; num_vars],
+            levels: vec![0; num_vars],
+            reasons: vec![None; num_vars],
+            trail: Vec::new(),
+            trail_limits: Vec::new(),
+            head: 0,
+            activity: vec![0.0; num_vars],
+            increment: 1.0,
+            order: Order::new(num_vars),
+            phases: vec![false; num_vars]
+        };
+        for index in 0..solver.clauses.len() {
+            match solver.clauses[index][..] {
+                [] => return Err(index),
+                [lit] => match solver.value(lit) {
+                    TriState::False => return Err(index),
+                    TriState::Default => solver.assign(lit, Some(index)),
+                    TriState::True => {}
+                },
+                [first, second, ..] => {
+                    solver.watches[first.0].push(index);
+                    solver.watches[second.0].push(index);
+                }
+            }
+        }
+        for (var, &value) in assumptions.iter().enumerate() {
+            let lit = match value {
+                TriState::True => Lit::positive(var),
+                TriState::False => Lit::negative(var),
+                TriState::Default => continue
+            };
+            match solver.value(lit) {
+                TriState::False => return Err(solver.reasons[var].unwrap_or_default()),
+                TriState::Default => solver.assign(lit, None),
+                TriState::True => {}
+            }
+        }
+        Ok(solver)
+    }
+
+    fn value(&self, lit: Lit) -> TriState {
+        lit.eval(&self.values)
+    }
+
+    fn level(&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    fn assign(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var();
+        self.values[var] = TriState::from(!lit.is_negative());
+        self.levels[var] = self.level();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Assigns every literal implied by the clauses, returning the index of a falsified clause
+    /// on conflict
+    fn propagate(&mut self) -> Option<usize> {
+        while self.head < self.trail.len() {
+            let falsified = !self.trail[self.head];
+            self.head += 1;
+
+            let mut watching = core::mem::take(&mut self.watches[falsified.0]);
+            let mut kept = 0;
+            let mut conflict = None;
+            let mut i = 0;
+            while i < watching.len() {
+                let index = watching[i];
+                i += 1;
+
+                let clause = &mut self.clauses[index];
+                let values = &self.values;
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+                if other.eval(values) == TriState::True {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+                if let Some(k) = (2..clause.len()).find(|&k| clause[k].eval(values) != TriState::False) {
+                    clause.swap(1, k);
+                    self.watches[clause[1].0].push(index);
+                    continue;
+                }
+
+                watching[kept] = index;
+                kept += 1;
+                if other.eval(&self.values) == TriState::False {
+                    conflict = Some(index);
+                    while i < watching.len() {
+                        watching[kept] = watching[i];
+                        kept += 1;
+                        i += 1;
+                    }
+                } else {
+                    self.assign(other, Some(index));
+                }
+            }
+            watching.truncate(kept);
+            self.watches[falsified.0] = watching;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Learns the first unique implication point clause of a conflict, returning it with the
+    /// asserting literal first and the level to backjump to
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![Lit(0)];
+        let mut seen = vec![false; self.values.len()];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut reason = conflict;
+        let mut implied = None;
+
+        loop {
+            let skip = if implied.is_some() { 1 } else { 0 };
+            for k in skip..self.clauses[reason].len() {
+                let lit = self.clauses[reason][k];
+                let var = lit.var();
+                if !seen[var] && self.levels[var] > 0 {
+                    seen[var] = true;
+                    self.bump(var);
+                    if self.levels[var] == self.level() {
+                        pending += 1;
+                    } else {
+                        learnt.push(lit);
+                    }
+                }
+            }
+            loop {
+                index -= 1;
+                if seen[self.trail[index].var()] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            seen[lit.var()] = false;
+            pending -= 1;
+            implied = Some(lit);
+            if pending == 0 {
+                break;
+            }
+            reason = self.reasons[lit.var()].expect("only decisions lack a reason");
+        }
+        learnt[0] = !implied.expect("a conflict involves the current level");
+
+        // Watch the literal from the highest remaining level second, so it is the first
+        // to become unassigned again
+        let mut backjump = 0;
+        for k in 1..learnt.len() {
+            let level = self.levels[learnt[k].var()];
+            if level > backjump {
+                backjump = level;
+                learnt.swap(1, k);
+            }
+        }
+        (learnt, backjump)
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.increment;
+        if self.activity[var] > ACTIVITY_LIMIT {
+            for activity in &mut self.activity {
+                *activity /= ACTIVITY_LIMIT;
+            }
+            self.increment /= ACTIVITY_LIMIT;
+        }
+        self.order.increased(var, &self.activity);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if let Some(&limit) = self.trail_limits.get(level) {
+            for lit in self.trail.drain(limit..) {
+                let var = lit.var();
+                self.phases[var] = !lit.is_negative();
+                self.values[var] = TriState::Default;
+                self.reasons[var] = None;
+                self.order.insert(var, &self.activity);
+            }
+            self.trail_limits.truncate(level);
+            self.head = self.trail.len();
+        }
+    }
+
+    /// Returns the unassigned variable with the highest activity
+    fn pick(&mut self) -> Option<usize> {
+        while let Some(var) = self.order.pop(&self.activity) {
+            if self.values[var] == TriState::Default {
+                return Some(var);
+            }
+        }
+        None
+    }
+
+    fn search(&mut self) -> Solution {
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.level() == 0 {
+                    return Solution::Unsat;
+                }
+                let (learnt, backjump) = self.analyze(conflict);
+                self.backtrack(backjump);
+                if learnt.len() == 1 {
+                    self.assign(learnt[0], None);
+                } else {
+                    let index = self.clauses.len();
+                    self.watches[learnt[0].0].push(index);
+                    self.watches[learnt[1].0].push(index);
+                    self.assign(learnt[0], Some(index));
+                    self.clauses.push(learnt);
+                }
+                self.increment *= ACTIVITY_GROWTH;
+            } else {
+                match self.pick() {
+                    Some(var) => {
+                        self.trail_limits.push(self.trail.len());
+                        let lit = if self.phases[var] { Lit::positive(var) } else { Lit::negative(var) };
+                        self.assign(lit, None);
+                    }
+                    None => return Solution::Sat(core::mem::take(&mut self.values))
+                }
+            }
+        }
+    }
+}
+
+/// A binary max-heap of variables ordered by activity, with ties going to the lowest variable
+///
+/// Assigned variables are left in the heap and skipped when popped, and unassigned ones are
+/// put back on backtracking.
+struct Order {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>
+}
+
+impl Order {
+    /// Returns a heap of every variable, which is ordered while all activities are equal
+    fn new(num_vars: usize) -> Self {
+        Self { heap: (0..num_vars).collect(), positions: (0..num_vars).map(Some).collect() }
+    }
+
+    fn insert(&mut self, var: usize, activity: &[f64]) {
+        if self.positions[var].is_none() {
+            self.positions[var] = Some(self.heap.len());
+            self.heap.push(var);
+            self.sift_up(self.heap.len() - 1, activity);
+        }
+    }
+
+    /// Restores the order after the activity of a variable grew
+    fn increased(&mut self, var: usize, activity: &[f64]) {
+        if let Some(position) = self.positions[var] {
+            self.sift_up(position, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        let last = self.heap.pop()?;
+        if self.heap.is_empty() {
+            self.positions[last] = None;
+            return Some(last);
+        }
+        let top = core::mem::replace(&mut self.heap[0], last);
+        self.positions[top] = None;
+        self.positions[last] = Some(0);
+        self.sift_down(0, activity);
+        Some(top)
+    }
+
+    fn before(a: usize, b: usize, activity: &[f64]) -> bool {
+        activity[a] > activity[b] || (activity[a] == activity[b] && a < b)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.positions[self.heap[i]] = Some(i);
+        self.positions[self.heap[j]] = Some(j);
+    }
+
+    fn sift_up(&mut self, mut position: usize, activity: &[f64]) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !Self::before(self.heap[position], self.heap[parent], activity) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize, activity: &[f64]) {
+        loop {
+            let mut best = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len() && Self::before(self.heap[child], self.heap[best], activity) {
+                    best = child;
+                }
+            }
+            if best == position {
+                break;
+            }
+            self.swap(position, best);
+            position = best;
+        }
+    }
+}
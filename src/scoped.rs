@@ -0,0 +1,298 @@
+//! Tri-state settings inherited along paths.
+//!
+//! [`ScopedTriStates`] holds settings for paths such as `net::tls`, where a path without a
+//! definite setting of its own inherits from its nearest ancestor that has one, and the root
+//! falls back to a default with `TriState::or_else`. Settings are written as comma separated
+//! directives in the style of `RUST_LOG`, where a bare value such as `off` sets the root and a
+//! bare path such as `net::http` is turned on.
+//!
+//! ```rust
+//! use tristate::TriState;
+//! use tristate::scoped::ScopedTriStates;
+//!
+//! let scopes: ScopedTriStates = "trace=on,net=off,net::tls=default,net::http::client".parse().unwrap();
+//!
+//! assert!(scopes.is_enabled("trace::spans", false));
+//! assert!(!scopes.is_enabled("net::tls", true));
+//! assert!(scopes.is_enabled("net::http::client::pool", false));
+//! assert!(scopes.is_enabled("db", true));
+//!
+//! let lookup = scopes.lookup("net::tls::handshake", true);
+//! assert_eq!(lookup.directive().map(ToString::to_string), Some("net=off".to_owned()));
+//! assert_eq!(scopes.get("net::tls"), TriState::Default);
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use crate::TriState;
+use crate::parse::ParseTriStateError;
+
+/// The separator between the segments of a path
+const SEPARATOR: &str = "::";
+
+/// A setting for a path
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Directive {
+    path: String,
+    value: TriState
+}
+
+impl Directive {
+    /// Returns the path the setting applies to, which is empty for the root
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the setting
+    pub fn value(&self) -> TriState {
+        self.value
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self.value {
+            TriState::True => write!(f, "{}=on", self.path),
+            TriState::False => write!(f, "{}=off", self.path),
+            TriState::Default => write!(f, "{}=default", self.path)
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+struct Scope {
+    directive: Option<Directive>,
+    children: BTreeMap<String, Scope>
+}
+
+/// A prefix tree of tri-state settings keyed by path segments separated by `::`
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct ScopedTriStates {
+    root: Scope
+}
+
+impl ScopedTriStates {
+    /// Returns a tree without settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value for a path, where the empty path is the root, and returns the previous
+    /// value
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path is not empty but has an empty segment, as in `net::` or `a::::b`, or a
+    /// segment with a comma, an equals sign or whitespace, which directives cannot express.
+    ///
+    /// ```rust,should_panic
+    /// use tristate::TriState;
+    /// use tristate::scoped::ScopedTriStates;
+    ///
+    /// ScopedTriStates::new().insert("net=tls", TriState::True);
+    /// ```
+    pub fn insert(&mut self, path: &str, value: TriState) -> TriState {
+        assert!(is_valid_path(path), "invalid path {:?}", path);
+        let mut scope = &mut self.root;
+        for segment in segments(path) {
+            scope = scope.children.entry(segment.to_owned()).or_default();
+        }
+        let previous = scope.directive.replace(Directive { path: path.to_owned(), value });
+        previous.map_or(TriState::Default, |directive| directive.value)
+    }
+
+    /// Returns the value set for exactly this path, without inheritance
+    pub fn get(&self, path: &str) -> TriState {
+        let mut scope = &self.root;
+        for segment in segments(path) {
+            match scope.children.get(segment) {
+                Some(child) => scope = child,
+                None => return TriState::Default
+            }
+        }
+        scope.directive.as_ref().map_or(TriState::Default, |directive| directive.value)
+    }
+
+    /// Returns the effective setting for a path and the directive that decided it
+    ///
+    /// The setting comes from the path itself or its nearest ancestor with a definite value,
+    /// and is `default` if none of them has one.
+    pub fn lookup(&self, path: &str, default: bool) -> Lookup<'_> {
+        let mut decided = None;
+        let mut scope = &self.root;
+        let mut segments = segments(path);
+        loop {
+            if let Some(directive) = &scope.directive {
+                if directive.value != TriState::Default {
+                    decided = Some(directive);
+                }
+            }
+            match segments.next().and_then(|segment| scope.children.get(segment)) {
+                Some(child) => scope = child,
+                None => break
+            }
+        }
+        let value = decided.map_or(TriState::Default, |directive| directive.value);
+        Lookup { enabled: value.or_else(default), directive: decided }
+    }
+
+    /// Returns the effective setting for a path, or `default` if neither it nor any of its
+    /// ancestors has a definite value
+    pub fn is_enabled(&self, path: &str, default: bool) -> bool {
+        self.lookup(path, default).is_enabled()
+    }
+
+    /// Returns an iterator over the directives, parents before their children and siblings in
+    /// order of their segments
+    pub fn directives(&self) -> impl Iterator<Item = &Directive> {
+        let mut stack = alloc::vec![&self.root];
+        core::iter::from_fn(move || {
+            while let Some(scope) = stack.pop() {
+                stack.extend(scope.children.values().rev());
+                if scope.directive.is_some() {
+                    return scope.directive.as_ref();
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns true if there are no settings
+    pub fn is_empty(&self) -> bool {
+        self.directives().next().is_none()
+    }
+}
+
+impl FromStr for ScopedTriStates {
+    type Err = ParseDirectiveError;
+
+    /// Parses comma separated directives, of which later ones replace earlier ones for the same
+    /// path
+    ///
+    /// A directive is either `path=value`, where the value is any word `TriState` parses, or a
+    /// bare word. A bare word that parses as a tri-state sets the root, as does a directive with
+    /// an empty path such as `=off`, and any other bare path is turned on.
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::scoped::{ParseDirectiveError, ScopedTriStates};
+    ///
+    /// let scopes: ScopedTriStates = " on , app::ui = yes, no ".parse().unwrap();
+    /// assert_eq!(scopes.to_string(), "=off,app::ui=on");
+    /// assert_eq!(scopes.get(""), TriState::False);
+    /// assert!(!scopes.is_enabled("no::tls", true));
+    /// assert_eq!(scopes, "=off,app::ui=on".parse().unwrap());
+    ///
+    /// let error = "net=maybe".parse::<ScopedTriStates>().unwrap_err();
+    /// assert!(matches!(error, ParseDirectiveError::InvalidValue { .. }));
+    /// assert_eq!("net::=on".parse::<ScopedTriStates>(), Err(ParseDirectiveError::InvalidPath { directive: "net::=on".to_owned() }));
+    /// assert!(matches!("net tls=on".parse::<ScopedTriStates>(), Err(ParseDirectiveError::InvalidPath { .. })));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scopes = Self::new();
+        for directive in s.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            let (path, value) = match directive.split_once('=') {
+                Some((path, value)) => {
+                    let value = value.trim().parse().map_err(|source| ParseDirectiveError::InvalidValue {
+                        directive: directive.to_owned(),
+                        source
+                    })?;
+                    (path.trim(), value)
+                }
+                None => match directive.parse() {
+                    Ok(value) => ("", value),
+                    Err(_) => (directive, TriState::True)
+                }
+            };
+            if !is_valid_path(path) {
+                return Err(ParseDirectiveError::InvalidPath { directive: directive.to_owned() });
+            }
+            scopes.insert(path, value);
+        }
+        Ok(scopes)
+    }
+}
+
+impl Display for ScopedTriStates {
+    /// Formats the settings as directives that parse back into the same tree
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        for (index, directive) in self.directives().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            Display::fmt(directive, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The effective setting for a path in [`ScopedTriStates`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Lookup<'a> {
+    enabled: bool,
+    directive: Option<&'a Directive>
+}
+
+impl<'a> Lookup<'a> {
+    /// Returns the effective setting
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the directive that decided the setting, or `None` if it is the default
+    pub fn directive(&self) -> Option<&'a Directive> {
+        self.directive
+    }
+}
+
+/// The error returned when parsing directives for [`ScopedTriStates`] fails
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ParseDirectiveError {
+    /// The path of a directive has an empty segment or a segment with whitespace
+    InvalidPath {
+        /// The directive at fault
+        directive: String
+    },
+    /// The value of a directive is not a tri-state
+    InvalidValue {
+        /// The directive at fault
+        directive: String,
+        /// The error from parsing the value
+        source: ParseTriStateError
+    }
+}
+
+impl Display for ParseDirectiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::InvalidPath { directive } => write!(f, "invalid path in directive {:?}", directive),
+            Self::InvalidValue { directive, source } => write!(f, "directive {:?}: {}", directive, source)
+        }
+    }
+}
+
+impl Error for ParseDirectiveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidPath { .. } => None,
+            Self::InvalidValue { source, .. } => Some(source)
+        }
+    }
+}
+
+/// Returns the segments of a path, of which the empty path has none
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split(SEPARATOR).filter(move |_| !path.is_empty())
+}
+
+fn is_valid_path(path: &str) -> bool {
+    let is_valid_segment = |segment: &str| {
+        !segment.is_empty() && !segment.contains(|c: char| c == ',' || c == '=' || c.is_whitespace())
+    };
+    path.is_empty() || segments(path).all(is_valid_segment)
+}
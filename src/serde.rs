@@ -0,0 +1,230 @@
+//! Alternative serde representations of `TriState` for use with `#[serde(with = "...")]`.
+//!
+//! The derived implementations write the variant names `"False"`, `"Default"` and `"True"`. The
+//! modules here offer other wire formats:
+//!
+//! | Module                | `False`   | `Default`   | `True`   |
+//! |-----------------------|-----------|-------------|----------|
+//! | [`as_option_bool`]    | `false`   | `null`      | `true`   |
+//! | [`as_i8`]             | `-1`      | `0`         | `1`      |
+//! | [`as_lowercase_str`]  | `"false"` | `"default"` | `"true"` |
+//!
+//! Pairing a module with `#[serde(default, skip_serializing_if = "tristate::serde::is_default")]`
+//! leaves `TriState::Default` values out when serializing and reads missing fields back as
+//! `TriState::Default`.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use tristate::TriState;
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Flags {
+//!     #[serde(with = "tristate::serde::as_option_bool")]
+//!     color: TriState,
+//!     #[serde(with = "tristate::serde::as_i8")]
+//!     level: TriState,
+//!     #[serde(
+//!         default,
+//!         skip_serializing_if = "tristate::serde::is_default",
+//!         with = "tristate::serde::as_lowercase_str"
+//!     )]
+//!     unicode: TriState
+//! }
+//!
+//! let flags = Flags { color: TriState::Default, level: TriState::False, unicode: TriState::Default };
+//! let json = serde_json::to_string(&flags).unwrap();
+//!
+//! assert_eq!(json, r#"{"color":null,"level":-1}"#);
+//! assert_eq!(serde_json::from_str::<Flags>(&json).unwrap(), flags);
+//! ```
+
+use crate::TriState;
+
+/// Returns true if the value is `TriState::Default`, for use with `skip_serializing_if`
+pub fn is_default(value: &TriState) -> bool {
+    value == &TriState::Default
+}
+
+/// Represents a `TriState` as an optional boolean, where `TriState::Default` is `null`
+pub mod as_option_bool {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::TriState;
+
+    /// Serializes the tri-state as an optional boolean
+    pub fn serialize<S: Serializer>(value: &TriState, serializer: S) -> Result<S::Ok, S::Error> {
+        Option::<bool>::from(value).serialize(serializer)
+    }
+
+    /// Deserializes the tri-state from an optional boolean
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TriState, D::Error> {
+        Option::<bool>::deserialize(deserializer).map(TriState::from)
+    }
+}
+
+/// Represents a `TriState` as the integers `-1`, `0` and `1`
+pub mod as_i8 {
+    use ::serde::de::{Error, Unexpected};
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::TriState;
+
+    /// Serializes the tri-state as `-1`, `0` or `1`
+    pub fn serialize<S: Serializer>(value: &TriState, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i8(match value {
+            TriState::False => -1,
+            TriState::Default => 0,
+            TriState::True => 1
+        })
+    }
+
+    /// Deserializes the tri-state from `-1`, `0` or `1`
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TriState, D::Error> {
+        match i8::deserialize(deserializer)? {
+            -1 => Ok(TriState::False),
+            0 => Ok(TriState::Default),
+            1 => Ok(TriState::True),
+            other => Err(D::Error::invalid_value(Unexpected::Signed(other.into()), &"-1, 0 or 1"))
+        }
+    }
+}
+
+/// Represents a `TriState` as the strings `"false"`, `"default"` and `"true"`
+pub mod as_lowercase_str {
+    use ::serde::de::{Error, Unexpected, Visitor};
+    use ::serde::{Deserializer, Serializer};
+    use core::fmt::Formatter;
+
+    use crate::TriState;
+
+    /// Serializes the tri-state as `"false"`, `"default"` or `"true"`
+    pub fn serialize<S: Serializer>(value: &TriState, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match value {
+            TriState::False => "false",
+            TriState::Default => "default",
+            TriState::True => "true"
+        })
+    }
+
+    /// Deserializes the tri-state from `"false"`, `"default"` or `"true"`
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TriState, D::Error> {
+        deserializer.deserialize_str(LowercaseVisitor)
+    }
+
+    struct LowercaseVisitor;
+
+    impl Visitor<'_> for LowercaseVisitor {
+        type Value = TriState;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+            f.write_str("\"false\", \"default\" or \"true\"")
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<TriState, E> {
+            match v {
+                "false" => Ok(TriState::False),
+                "default" => Ok(TriState::Default),
+                "true" => Ok(TriState::True),
+                _ => Err(E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+    }
+}
+
+/// Accepts every representation of a `TriState` when deserializing, to ease migrating stored
+/// data between formats
+///
+/// Booleans, `null`, the integers `-1`, `0` and `1`, and the variant names in any case are all
+/// accepted. Values are serialized as optional booleans, like [`as_option_bool`]. As the input
+/// is inspected to decide its form, this only works with self-describing formats such as JSON.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use tristate::TriState;
+///
+/// #[derive(Deserialize)]
+/// struct Flag(#[serde(with = "tristate::serde::lenient")] TriState);
+///
+/// for (json, value) in [
+///     ("true", TriState::True),
+///     ("null", TriState::Default),
+///     ("-1", TriState::False),
+///     ("\"Default\"", TriState::Default),
+///     ("\"true\"", TriState::True)
+/// ].iter() {
+///     assert_eq!(serde_json::from_str::<Flag>(json).unwrap().0, *value);
+/// }
+/// assert!(serde_json::from_str::<Flag>("2").is_err());
+/// ```
+pub mod lenient {
+    use ::serde::de::{Error, Unexpected, Visitor};
+    use ::serde::{Deserializer, Serializer};
+    use core::fmt::Formatter;
+
+    use crate::TriState;
+
+    /// Serializes the tri-state as an optional boolean
+    pub fn serialize<S: Serializer>(value: &TriState, serializer: S) -> Result<S::Ok, S::Error> {
+        super::as_option_bool::serialize(value, serializer)
+    }
+
+    /// Deserializes the tri-state from any of its representations
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TriState, D::Error> {
+        deserializer.deserialize_any(LenientVisitor)
+    }
+
+    struct LenientVisitor;
+
+    impl<'de> Visitor<'de> for LenientVisitor {
+        type Value = TriState;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+            f.write_str("a boolean, null, -1, 0, 1 or the name of a tri-state")
+        }
+
+        fn visit_bool<E: Error>(self, v: bool) -> Result<TriState, E> {
+            Ok(TriState::from(v))
+        }
+
+        fn visit_i64<E: Error>(self, v: i64) -> Result<TriState, E> {
+            match v {
+                -1 => Ok(TriState::False),
+                0 => Ok(TriState::Default),
+                1 => Ok(TriState::True),
+                _ => Err(E::invalid_value(Unexpected::Signed(v), &self))
+            }
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<TriState, E> {
+            match v {
+                0 => Ok(TriState::Default),
+                1 => Ok(TriState::True),
+                _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<TriState, E> {
+            if v.eq_ignore_ascii_case("false") {
+                Ok(TriState::False)
+            } else if v.eq_ignore_ascii_case("default") || v.eq_ignore_ascii_case("null") {
+                Ok(TriState::Default)
+            } else if v.eq_ignore_ascii_case("true") {
+                Ok(TriState::True)
+            } else {
+                Err(E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        fn visit_none<E: Error>(self) -> Result<TriState, E> {
+            Ok(TriState::Default)
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<TriState, E> {
+            Ok(TriState::Default)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<TriState, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! Truth tables of functions over tri-states.
+//!
+//! A [`TruthTable`] lists the value of one or more functions for every combination of input
+//! values, and renders as plain text, Markdown, CSV or LaTeX with a choice of [`Symbols`].
+//!
+//! ```rust
+//! use tristate::logic::{Logic, StrongKleene, WeakKleene};
+//! use tristate::table::{Format, Symbols, TruthTable};
+//!
+//! let table = TruthTable::new(["a", "b"])
+//!     .column("a && b", |v| v[0] & v[1])
+//!     .column("a -> b", |v| v[0].implies(v[1]));
+//!
+//! assert_eq!(table.to_string(), "\
+//! a | b | a && b | a -> b
+//! --+---+--------+-------
+//! T | T | T      | T
+//! T | U | U      | U
+//! T | F | F      | F
+//! U | T | U      | T
+//! U | U | U      | U
+//! U | F | F      | U
+//! F | T | F      | T
+//! F | U | F      | T
+//! F | F | F      | T
+//! ");
+//!
+//! // Where strong and weak Kleene disagree on disjunction
+//! let strong = TruthTable::new(["a", "b"]).column("strong", |v| StrongKleene::or(v[0], v[1]));
+//! let weak = TruthTable::new(["a", "b"]).column("weak", |v| WeakKleene::or(v[0], v[1]));
+//! assert_eq!(strong.diff(&weak).render(Format::Markdown).symbols(Symbols::DIGITS).to_string(), "\
+//! | a | b | strong | weak |
+//! |---|---|--------|------|
+//! | 1 | ½ | 1      | ½    |
+//! | ½ | 1 | 1      | ½    |
+//! ");
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+
+use crate::TriState;
+use crate::expr::Expr;
+
+/// The input values in the order the rows list them
+const VALUES: [TriState; 3] = [TriState::True, TriState::Default, TriState::False];
+
+/// A table of the values of functions over tri-states for combinations of input values
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TruthTable {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    rows: Vec<Vec<TriState>>
+}
+
+impl TruthTable {
+    /// Returns a table without output columns that lists every combination of values of the
+    /// named inputs, with `TriState::True` first and the first input changing slowest
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(inputs: I) -> Self {
+        let inputs: Vec<String> = inputs.into_iter().map(Into::into).collect();
+        let mut rows = Vec::new();
+        let mut row = alloc::vec![0; inputs.len()];
+        loop {
+            rows.push(row.iter().map(|&index| VALUES[index]).collect());
+            // Step to the next combination, counting in base three from the last input
+            match row.iter().rposition(|&index| index < VALUES.len() - 1) {
+                Some(position) => {
+                    row[position] += 1;
+                    row[position + 1..].iter_mut().for_each(|index| *index = 0);
+                }
+                None => break
+            }
+        }
+        Self { inputs, outputs: Vec::new(), rows }
+    }
+
+    /// Returns a table of a single function of the named inputs, with its column named `f`
+    ///
+    /// ```rust
+    /// use tristate::table::TruthTable;
+    ///
+    /// let table = TruthTable::from_fn(["a"], |v| !v[0]);
+    /// assert_eq!(table.outputs(), ["f"]);
+    /// assert_eq!(table.len(), 3);
+    /// ```
+    pub fn from_fn<I, S, F>(inputs: I, f: F) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        F: Fn(&[TriState]) -> TriState
+    {
+        Self::new(inputs).column("f", f)
+    }
+
+    /// Returns a table of an expression, with a column for each of its variables in order and
+    /// the expression itself as the output column
+    ///
+    /// ```rust
+    /// use tristate::TriState;
+    /// use tristate::expr::Expr;
+    /// use tristate::table::TruthTable;
+    ///
+    /// let expr: Expr<String> = "b || !a".parse().unwrap();
+    /// let table = TruthTable::from_expr(&expr);
+    ///
+    /// assert_eq!(table.inputs(), ["a", "b"]);
+    /// assert_eq!(table.outputs(), ["b || !a"]);
+    /// assert_eq!(table.get(&[TriState::Default, TriState::False]), Some(&[TriState::Default][..]));
+    /// ```
+    ///
+    /// Markdown escapes the `|` of disjunctions in headers so they do not split the column.
+    ///
+    /// ```rust
+    /// use tristate::expr::Expr;
+    /// use tristate::table::{Format, TruthTable};
+    ///
+    /// let expr: Expr<String> = "a || b".parse().unwrap();
+    /// assert_eq!(TruthTable::from_expr(&expr).render(Format::Markdown).to_string(), "\
+    /// | a | b | a \\|\\| b |
+    /// |---|---|----------|
+    /// | T | T | T        |
+    /// | T | U | T        |
+    /// | T | F | T        |
+    /// | U | T | T        |
+    /// | U | U | U        |
+    /// | U | F | U        |
+    /// | F | T | T        |
+    /// | F | U | U        |
+    /// | F | F | F        |
+    /// ");
+    /// assert!(TruthTable::from_expr(&expr).to_string().starts_with("a | b | a || b\n"));
+    /// ```
+    pub fn from_expr<K: Ord + Display>(expr: &Expr<K>) -> Self {
+        let vars: Vec<&K> = expr.free_vars().into_iter().collect();
+        Self::new(vars.iter().map(ToString::to_string)).column(expr.to_string(), |values| {
+            expr.eval(&|key: &K| vars.iter().position(|var| *var == key).map_or(TriState::Default, |index| values[index]))
+        })
+    }
+
+    /// Returns the table with an output column holding the value of `f` for the inputs of each
+    /// row
+    pub fn column<S: Into<String>, F: Fn(&[TriState]) -> TriState>(mut self, name: S, f: F) -> Self {
+        let inputs = self.inputs.len();
+        for row in &mut self.rows {
+            let value = f(&row[..inputs]);
+            row.push(value);
+        }
+        self.outputs.push(name.into());
+        self
+    }
+
+    /// Returns the names of the inputs
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Returns the names of the output columns
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Returns the number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if the table has no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns an iterator over the rows, each split into its input and output values
+    pub fn rows(&self) -> impl Iterator<Item = (&[TriState], &[TriState])> {
+        self.rows.iter().map(move |row| row.split_at(self.inputs.len()))
+    }
+
+    /// Returns the output values of the row with the given input values
+    pub fn get(&self, inputs: &[TriState]) -> Option<&[TriState]> {
+        self.rows().find(|(row, _)| *row == inputs).map(|(_, outputs)| outputs)
+    }
+
+    /// Returns a table of the rows where the outputs of the two tables differ, with the output
+    /// columns of this table followed by those of `other`
+    ///
+    /// Rows are matched by their input values, so the inputs of the two tables are compared by
+    /// position rather than by name. Rows missing from `other` are left out.
+    pub fn diff(&self, other: &TruthTable) -> TruthTable {
+        let rows = self.rows()
+            .filter_map(|(inputs, outputs)| {
+                let theirs = other.get(inputs)?;
+                if outputs == theirs {
+                    return None;
+                }
+                Some(inputs.iter().chain(outputs).chain(theirs).copied().collect())
+            })
+            .collect();
+        TruthTable {
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.iter().chain(&other.outputs).cloned().collect(),
+            rows
+        }
+    }
+
+    /// Returns the table rendered in the given format, with letter symbols unless others are
+    /// chosen
+    pub fn render(&self, format: Format) -> Render<'_> {
+        Render { table: self, format, symbols: Symbols::LETTERS }
+    }
+}
+
+impl Display for TruthTable {
+    /// Formats the table as plain text with letter symbols
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Display::fmt(&self.render(Format::Ascii), f)
+    }
+}
+
+/// The format of a rendered [`TruthTable`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Format {
+    /// Plain text with aligned columns
+    Ascii,
+    /// A Markdown table, with `|` in headers and symbols escaped
+    Markdown,
+    /// Comma separated values with a header row
+    Csv,
+    /// A LaTeX `tabular` environment, with the headers escaped and the symbols written as is
+    Latex
+}
+
+/// The symbols a [`TruthTable`] is rendered with
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Symbols {
+    true_symbol: &'static str,
+    false_symbol: &'static str,
+    unknown_symbol: &'static str
+}
+
+impl Symbols {
+    /// `T`, `F` and `U`
+    pub const LETTERS: Symbols = Symbols::new("T", "F", "U");
+    /// `1`, `0` and `½`
+    pub const DIGITS: Symbols = Symbols::new("1", "0", "½");
+    /// `⊤`, `⊥` and `?`
+    pub const LOGIC: Symbols = Symbols::new("⊤", "⊥", "?");
+
+    /// Returns the symbols for true, false and unknown values
+    pub const fn new(true_symbol: &'static str, false_symbol: &'static str, unknown_symbol: &'static str) -> Self {
+        Self { true_symbol, false_symbol, unknown_symbol }
+    }
+
+    /// Returns the symbol for a value
+    pub fn symbol(&self, value: TriState) -> &'static str {
+        match value {
+            TriState::False => self.false_symbol,
+            TriState::Default => self.unknown_symbol,
+            TriState::True => self.true_symbol
+        }
+    }
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Self::LETTERS
+    }
+}
+
+/// A [`TruthTable`] rendered in a [`Format`], which is written out through `Display`
+///
+/// ```rust
+/// use tristate::table::{Format, Symbols, TruthTable};
+///
+/// let table = TruthTable::new(["a"]).column("!a", |v| !v[0]);
+///
+/// assert_eq!(table.render(Format::Csv).to_string(), "a,!a\nT,F\nU,U\nF,T\n");
+/// assert_eq!(table.render(Format::Ascii).symbols(Symbols::LOGIC).to_string(), "a | !a\n--+---\n⊤ | ⊥\n? | ?\n⊥ | ⊤\n");
+/// assert_eq!(
+///     table.render(Format::Latex).symbols(Symbols::new("$\\top$", "$\\bot$", "?")).to_string(),
+///     "\\begin{tabular}{c|c}\na & !a \\\\\n\\hline\n$\\top$ & $\\bot$ \\\\\n? & ? \\\\\n$\\bot$ & $\\top$ \\\\\n\\end{tabular}\n"
+/// );
+///
+/// let table = TruthTable::new(["a", "b"]).column("a <-> b", |v| v[0].equiv(v[1])).column("a || b", |v| v[0] | v[1]);
+/// let latex = table.render(Format::Latex).to_string();
+/// assert_eq!(latex.lines().nth(1), Some("a & b & a \\textless{}-\\textgreater{} b & a \\textbar{}\\textbar{} b \\\\"));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Render<'a> {
+    table: &'a TruthTable,
+    format: Format,
+    symbols: Symbols
+}
+
+impl Render<'_> {
+    /// Returns the rendering with the given symbols
+    pub fn symbols(mut self, symbols: Symbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    fn headers(&self) -> impl Iterator<Item = &str> {
+        self.table.inputs.iter().chain(&self.table.outputs).map(String::as_str)
+    }
+
+    fn cells<'b>(&'b self, row: &'b [TriState]) -> impl Iterator<Item = &'static str> + 'b {
+        row.iter().map(move |&value| self.symbols.symbol(value))
+    }
+
+    /// Returns text as written in the format, with `|` escaped in Markdown so that it is not
+    /// read as a column separator
+    fn escape<'b>(&self, text: &'b str) -> Cow<'b, str> {
+        if self.format == Format::Markdown && text.contains('|') {
+            Cow::Owned(text.replace('|', "\\|"))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Writes a line of aligned cells, without padding after the last cell
+    fn write_aligned<'b, I: Iterator<Item = &'b str>>(&self, f: &mut Formatter<'_>, cells: I, widths: &[usize], separator: &str, ends: bool) -> Result<(), core::fmt::Error> {
+        if ends {
+            f.write_str("| ")?;
+        }
+        for (index, (cell, &width)) in cells.zip(widths).enumerate() {
+            if index > 0 {
+                f.write_str(separator)?;
+            }
+            f.write_str(cell)?;
+            if ends || index + 1 < widths.len() {
+                write!(f, "{:width$}", "", width = width - cell.chars().count())?;
+            }
+        }
+        f.write_str(if ends { " |\n" } else { "\n" })
+    }
+}
+
+impl Display for Render<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let table = self.table;
+        match self.format {
+            Format::Ascii | Format::Markdown => {
+                let markdown = self.format == Format::Markdown;
+                let headers: Vec<Cow<'_, str>> = self.headers().map(|header| self.escape(header)).collect();
+                let rows: Vec<Vec<Cow<'_, str>>> = table.rows.iter()
+                    .map(|row| self.cells(row).map(|cell| self.escape(cell)).collect())
+                    .collect();
+                let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+                for row in &rows {
+                    for (width, cell) in widths.iter_mut().zip(row) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+
+                self.write_aligned(f, headers.iter().map(AsRef::as_ref), &widths, " | ", markdown)?;
+                let rules: Vec<String> = widths.iter().map(|&width| "-".repeat(width + if markdown { 2 } else { 0 })).collect();
+                if markdown {
+                    writeln!(f, "|{}|", rules.join("|"))?;
+                } else {
+                    writeln!(f, "{}", rules.iter().enumerate().map(|(index, rule)| {
+                        // Widen the rules to meet the spaces around each separator
+                        let left = if index > 0 { "-" } else { "" };
+                        let right = if index + 1 < rules.len() { "-" } else { "" };
+                        alloc::format!("{}{}{}", left, rule, right)
+                    }).collect::<Vec<_>>().join("+"))?;
+                }
+                for row in &rows {
+                    self.write_aligned(f, row.iter().map(AsRef::as_ref), &widths, " | ", markdown)?;
+                }
+                Ok(())
+            }
+            Format::Csv => {
+                let mut line = String::new();
+                for (index, header) in self.headers().enumerate() {
+                    if index > 0 {
+                        line.push(',');
+                    }
+                    if header.contains([',', '"', '\n', '\r']) {
+                        write!(line, "\"{}\"", header.replace('"', "\"\""))?;
+                    } else {
+                        line.push_str(header);
+                    }
+                }
+                writeln!(f, "{}", line)?;
+                for row in &table.rows {
+                    writeln!(f, "{}", self.cells(row).collect::<Vec<_>>().join(","))?;
+                }
+                Ok(())
+            }
+            Format::Latex => {
+                let spec = "c".repeat(table.inputs.len()) + "|" + &"c".repeat(table.outputs.len());
+                writeln!(f, "\\begin{{tabular}}{{{}}}", spec.trim_matches('|'))?;
+                let headers: Vec<String> = self.headers().map(escape_latex).collect();
+                writeln!(f, "{} \\\\", headers.join(" & "))?;
+                writeln!(f, "\\hline")?;
+                for row in &table.rows {
+                    writeln!(f, "{} \\\\", self.cells(row).collect::<Vec<_>>().join(" & "))?;
+                }
+                writeln!(f, "\\end{{tabular}}")
+            }
+        }
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '|' => escaped.push_str("\\textbar{}"),
+            _ => escaped.push(c)
+        }
+    }
+    escaped
+}
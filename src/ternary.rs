@@ -0,0 +1,461 @@
+//! Balanced ternary arithmetic built on `TriState`.
+//!
+//! The ordering of `TriState` puts `False < Default < True`, which matches the balanced ternary
+//! digits `-1 < 0 < +1`. A [`Trit`] views a tri-state as one of those digits, and
+//! [`BalancedTernary`] builds fixed-width signed integers out of them.
+//!
+//! Numbers are written most significant trit first using `+`, `0` and `-` for the digits.
+//!
+//! ```rust
+//! use std::convert::TryFrom;
+//! use tristate::ternary::Tryte;
+//!
+//! let a: Tryte = "+0-".parse().unwrap();
+//! let b = Tryte::try_from(-4).unwrap();
+//!
+//! assert_eq!(i64::try_from(a), Ok(8));
+//! assert_eq!((a + b).to_string(), "++");
+//! assert_eq!((a * b).to_string(), "--++");
+//! assert_eq!(-a, "-0+".parse().unwrap());
+//! ```
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter, Write};
+use core::ops::{Add, Mul, Neg, Sub};
+use core::str::FromStr;
+
+use crate::TriState;
+
+/// A single balanced ternary digit viewed from a `TriState`, where `TriState::False` is `-1`,
+/// `TriState::Default` is `0` and `TriState::True` is `+1`
+#[repr(transparent)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Trit(TriState);
+
+impl Trit {
+    /// The digit `-1`
+    pub const NEG: Trit = Trit(TriState::False);
+    /// The digit `0`
+    pub const ZERO: Trit = Trit(TriState::Default);
+    /// The digit `+1`
+    pub const POS: Trit = Trit(TriState::True);
+
+    /// Views a tri-state as a trit
+    pub const fn new(value: TriState) -> Self {
+        Self(value)
+    }
+
+    /// Returns the tri-state behind the trit
+    pub const fn get(self) -> TriState {
+        self.0
+    }
+
+    /// Returns the numeric value of the trit
+    pub fn to_i8(self) -> i8 {
+        match self.0 {
+            TriState::False => -1,
+            TriState::Default => 0,
+            TriState::True => 1
+        }
+    }
+
+    /// Adds two trits and an incoming carry, returning the sum digit and the outgoing carry
+    ///
+    /// ```rust
+    /// use tristate::ternary::Trit;
+    ///
+    /// assert_eq!(Trit::POS.add_with_carry(Trit::POS, Trit::ZERO), (Trit::NEG, Trit::POS));
+    /// assert_eq!(Trit::POS.add_with_carry(Trit::NEG, Trit::POS), (Trit::POS, Trit::ZERO));
+    /// assert_eq!(Trit::NEG.add_with_carry(Trit::NEG, Trit::NEG), (Trit::ZERO, Trit::NEG));
+    /// ```
+    pub fn add_with_carry(self, rhs: Trit, carry: Trit) -> (Trit, Trit) {
+        let total = self.to_i8() + rhs.to_i8() + carry.to_i8();
+        let digit = (total + 1).rem_euclid(3) - 1;
+        (Self::from_digit(digit), Self::from_digit((total - digit) / 3))
+    }
+
+    fn from_digit(digit: i8) -> Trit {
+        match digit {
+            -1 => Self::NEG,
+            0 => Self::ZERO,
+            _ => Self::POS
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self.0 {
+            TriState::False => '-',
+            TriState::Default => '0',
+            TriState::True => '+'
+        }
+    }
+
+    fn from_char(c: char) -> Option<Trit> {
+        match c {
+            '-' => Some(Self::NEG),
+            '0' => Some(Self::ZERO),
+            '+' => Some(Self::POS),
+            _ => None
+        }
+    }
+}
+
+impl From<TriState> for Trit {
+    fn from(value: TriState) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Trit> for TriState {
+    fn from(trit: Trit) -> Self {
+        trit.0
+    }
+}
+
+impl From<Trit> for i8 {
+    fn from(trit: Trit) -> Self {
+        trit.to_i8()
+    }
+}
+
+impl TryFrom<i8> for Trit {
+    type Error = TernaryRangeError;
+
+    /// Returns the trit with the given numeric value, which must be `-1`, `0` or `1`
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            -1..=1 => Ok(Self::from_digit(value)),
+            _ => Err(TernaryRangeError(()))
+        }
+    }
+}
+
+impl From<Ordering> for Trit {
+    /// Returns the trit for an ordering, where `Less` is `-1` and `Greater` is `+1`
+    ///
+    /// ```rust
+    /// use std::cmp::Ordering;
+    /// use tristate::ternary::Trit;
+    ///
+    /// assert_eq!(Trit::from(1.cmp(&2)), Trit::NEG);
+    /// assert_eq!(Ordering::from(Trit::POS), Ordering::Greater);
+    /// ```
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less => Self::NEG,
+            Ordering::Equal => Self::ZERO,
+            Ordering::Greater => Self::POS
+        }
+    }
+}
+
+impl From<Trit> for Ordering {
+    fn from(trit: Trit) -> Self {
+        trit.cmp(&Trit::ZERO)
+    }
+}
+
+impl Neg for Trit {
+    type Output = Trit;
+
+    fn neg(self) -> Trit {
+        Trit(!self.0)
+    }
+}
+
+impl Mul for Trit {
+    type Output = Trit;
+
+    fn mul(self, rhs: Trit) -> Trit {
+        Self::from_digit(self.to_i8() * rhs.to_i8())
+    }
+}
+
+impl Display for Trit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_char(self.to_char())
+    }
+}
+
+/// A signed integer of `N` balanced ternary digits, holding values between `-(3^N - 1) / 2`
+/// and `(3^N - 1) / 2`
+///
+/// The arithmetic operators panic on overflow, while the `checked_` methods return `None`.
+///
+/// ```rust
+/// use std::convert::TryFrom;
+/// use tristate::ternary::BalancedTernary;
+///
+/// type Small = BalancedTernary<3>;
+/// let all = -13..=13;
+///
+/// for a in all.clone() {
+///     let x = Small::try_from(a).unwrap();
+///     assert_eq!(x.to_string().parse(), Ok(x));
+///     for b in all.clone() {
+///         let y = Small::try_from(b).unwrap();
+///         let expect = |n: i64| Small::try_from(n).ok();
+///         assert_eq!(x.checked_add(y), expect(a + b));
+///         assert_eq!(x.checked_sub(y), expect(a - b));
+///         assert_eq!(x.checked_mul(y), expect(a * b));
+///         assert_eq!(x.cmp(&y), a.cmp(&b));
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BalancedTernary<const N: usize> {
+    /// Digits ordered from least to most significant
+    trits: [Trit; N]
+}
+
+/// A balanced ternary integer of six trits, holding values between `-364` and `364`
+pub type Tryte = BalancedTernary<6>;
+
+impl<const N: usize> BalancedTernary<N> {
+    /// The value zero
+    pub const ZERO: Self = Self { trits: [Trit::ZERO; N] };
+    /// The largest representable value, with every trit `+1`
+    pub const MAX: Self = Self { trits: [Trit::POS; N] };
+    /// The smallest representable value, with every trit `-1`
+    pub const MIN: Self = Self { trits: [Trit::NEG; N] };
+
+    /// Returns the integer made of the given trits, ordered from least to most significant
+    pub const fn from_trits(trits: [Trit; N]) -> Self {
+        Self { trits }
+    }
+
+    /// Returns the trits of the integer, ordered from least to most significant
+    pub const fn trits(&self) -> &[Trit; N] {
+        &self.trits
+    }
+
+    /// Returns true if the integer is zero
+    pub fn is_zero(&self) -> bool {
+        self.trits.iter().all(|&trit| trit == Trit::ZERO)
+    }
+
+    /// Returns the sign of the integer, which is its most significant non-zero trit
+    pub fn signum(&self) -> Trit {
+        self.trits.iter().rev().copied().find(|&trit| trit != Trit::ZERO).unwrap_or(Trit::ZERO)
+    }
+
+    /// Returns the sum of two integers, or `None` if it overflows
+    ///
+    /// ```rust
+    /// use std::convert::TryFrom;
+    /// use tristate::ternary::Tryte;
+    ///
+    /// assert_eq!(Tryte::MAX.checked_add(Tryte::try_from(1).unwrap()), None);
+    /// assert_eq!(Tryte::MAX.checked_add(Tryte::MIN), Some(Tryte::ZERO));
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let mut carry = Trit::ZERO;
+        let mut trits = [Trit::ZERO; N];
+        for (i, trit) in trits.iter_mut().enumerate() {
+            let (digit, next) = self.trits[i].add_with_carry(rhs.trits[i], carry);
+            *trit = digit;
+            carry = next;
+        }
+        if carry == Trit::ZERO { Some(Self { trits }) } else { None }
+    }
+
+    /// Returns the difference of two integers, or `None` if it overflows
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
+
+    /// Returns the product of two integers, or `None` if it overflows
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Long multiplication into a double width product, as partial sums may overflow even
+        // when the full product does not
+        let mut product = [[Trit::ZERO; N]; 2];
+        for (shift, &multiplier) in rhs.trits.iter().enumerate() {
+            let mut carry = Trit::ZERO;
+            for k in shift..2 * N {
+                let digit = self.trits.get(k - shift).map_or(Trit::ZERO, |&trit| trit * multiplier);
+                let sum = &mut product[k / N][k % N];
+                let (next_sum, next_carry) = sum.add_with_carry(digit, carry);
+                *sum = next_sum;
+                carry = next_carry;
+            }
+        }
+        let [trits, high] = product;
+        if high.iter().any(|&trit| trit != Trit::ZERO) {
+            return None;
+        }
+        Some(Self { trits })
+    }
+}
+
+impl<const N: usize> Default for BalancedTernary<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> Ord for BalancedTernary<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.trits.iter().rev().cmp(other.trits.iter().rev())
+    }
+}
+
+impl<const N: usize> PartialOrd for BalancedTernary<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Neg for BalancedTernary<N> {
+    type Output = Self;
+
+    /// Negates every trit, which can never overflow
+    fn neg(self) -> Self {
+        let mut trits = self.trits;
+        for trit in &mut trits {
+            *trit = -*trit;
+        }
+        Self { trits }
+    }
+}
+
+macro_rules! checked_op {
+    ($op:ident, $method:ident, $checked:ident, $verb:literal) => {
+        impl<const N: usize> $op for BalancedTernary<N> {
+            type Output = Self;
+
+            /// # Panics
+            ///
+            /// Panics if the result overflows.
+            fn $method(self, rhs: Self) -> Self {
+                self.$checked(rhs).expect(concat!("attempt to ", $verb, " with overflow"))
+            }
+        }
+    };
+}
+
+checked_op!(Add, add, checked_add, "add");
+checked_op!(Sub, sub, checked_sub, "subtract");
+checked_op!(Mul, mul, checked_mul, "multiply");
+
+impl<const N: usize> TryFrom<i64> for BalancedTernary<N> {
+    type Error = TernaryRangeError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        // Widened so that stepping past a digit cannot overflow at `i64::MIN`
+        let mut value = i128::from(value);
+        let mut trits = [Trit::ZERO; N];
+        for trit in &mut trits {
+            let digit = (value + 1).rem_euclid(3) - 1;
+            *trit = Trit::from_digit(digit as i8);
+            value = (value - digit) / 3;
+        }
+        if value == 0 { Ok(Self { trits }) } else { Err(TernaryRangeError(())) }
+    }
+}
+
+impl<const N: usize> TryFrom<BalancedTernary<N>> for i64 {
+    type Error = TernaryRangeError;
+
+    /// Returns the integer value, failing only for widths beyond the range of `i64`
+    fn try_from(value: BalancedTernary<N>) -> Result<Self, Self::Error> {
+        value.trits.iter().rev().try_fold(0i64, |total, &trit| {
+            total.checked_mul(3)?.checked_add(i64::from(trit.to_i8()))
+        }).ok_or(TernaryRangeError(()))
+    }
+}
+
+impl<const N: usize> Display for BalancedTernary<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let mut digits = self.trits.iter().rev().skip_while(|&&trit| trit == Trit::ZERO).peekable();
+        if digits.peek().is_none() {
+            return f.write_char('0');
+        }
+        digits.try_for_each(|trit| f.write_char(trit.to_char()))
+    }
+}
+
+impl<const N: usize> Debug for BalancedTernary<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "BalancedTernary<{}>({})", N, self)
+    }
+}
+
+impl<const N: usize> FromStr for BalancedTernary<N> {
+    type Err = ParseTernaryError;
+
+    /// Parses an integer written most significant trit first using `+`, `0` and `-`
+    ///
+    /// ```rust
+    /// use std::convert::TryFrom;
+    /// use tristate::ternary::{BalancedTernary, ParseTernaryError};
+    ///
+    /// assert_eq!("00+-".parse::<BalancedTernary<2>>().map(i64::try_from), Ok(Ok(2)));
+    /// assert_eq!("+--".parse::<BalancedTernary<2>>(), Err(ParseTernaryError::Overflow));
+    /// assert_eq!("+x".parse::<BalancedTernary<2>>(), Err(ParseTernaryError::InvalidDigit { index: 1, found: 'x' }));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseTernaryError::Empty);
+        }
+        // Count the trits from the most significant non-zero one while validating
+        let mut significant = 0;
+        for (index, found) in s.char_indices() {
+            let digit = Trit::from_char(found).ok_or(ParseTernaryError::InvalidDigit { index, found })?;
+            if significant > 0 || digit != Trit::ZERO {
+                significant += 1;
+            }
+        }
+        if significant > N {
+            return Err(ParseTernaryError::Overflow);
+        }
+        let mut trits = [Trit::ZERO; N];
+        for (trit, found) in trits.iter_mut().zip(s.chars().rev()) {
+            *trit = Trit::from_char(found).unwrap_or(Trit::ZERO);
+        }
+        Ok(Self { trits })
+    }
+}
+
+/// The error returned when a value does not fit in a [`Trit`] or [`BalancedTernary`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TernaryRangeError(());
+
+impl Display for TernaryRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str("value out of range for balanced ternary")
+    }
+}
+
+impl Error for TernaryRangeError {}
+
+/// The error returned when parsing a [`BalancedTernary`] fails
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParseTernaryError {
+    /// The input was empty
+    Empty,
+    /// The input contained a character other than `+`, `0` or `-`
+    InvalidDigit {
+        /// Byte offset of the character within the input
+        index: usize,
+        /// The character found
+        found: char
+    },
+    /// The value has more significant trits than the integer can hold
+    Overflow
+}
+
+impl Display for ParseTernaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::Empty => f.write_str("cannot parse balanced ternary from empty string"),
+            Self::InvalidDigit { index, found } => {
+                write!(f, "invalid balanced ternary digit {:?} at index {}", found, index)
+            }
+            Self::Overflow => f.write_str("balanced ternary number too large to fit in target type")
+        }
+    }
+}
+
+impl Error for ParseTernaryError {}
@@ -0,0 +1,112 @@
+//! Property tests for printing and parsing expressions.
+
+#![cfg(feature = "alloc")]
+
+use proptest::prelude::*;
+use tristate::TriState;
+use tristate::expr::Expr;
+
+fn constant() -> impl Strategy<Value = TriState> {
+    prop_oneof![Just(TriState::False), Just(TriState::Default), Just(TriState::True)]
+}
+
+fn variable() -> impl Strategy<Value = String> {
+    "[a-z_][a-z0-9_.]{0,6}".prop_filter("keywords are constants", |name| {
+        !["true", "false", "unknown", "default"].contains(&name.as_str())
+    })
+}
+
+fn expr() -> impl Strategy<Value = Expr<String>> {
+    let leaf = prop_oneof![constant().prop_map(Expr::Const), variable().prop_map(Expr::Var)];
+    leaf.prop_recursive(6, 64, 2, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|a| !a),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a & b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a | b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a ^ b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a.implies(b)),
+            (inner.clone(), inner).prop_map(|(a, b)| a.iff(b))
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn printing_then_parsing_gives_the_same_tree(expr in expr()) {
+        let printed = expr.to_string();
+        prop_assert_eq!(printed.parse::<Expr<String>>(), Ok(expr), "printed as {:?}", printed);
+    }
+
+    #[test]
+    fn parsing_ignores_whitespace_around_tokens(expr in expr()) {
+        let spaced = expr.to_string().replace('(', " ( ").replace(')', " ) ").replace('!', "\t!\n");
+        prop_assert_eq!(spaced.parse::<Expr<String>>(), Ok(expr));
+    }
+
+    #[test]
+    fn errors_point_inside_the_input(input in "[a-c!&|^<>()\\- ]{0,24}") {
+        if let Err(error) = input.parse::<Expr<String>>() {
+            prop_assert!(error.span().start <= error.span().end);
+            prop_assert!(error.span().end <= input.len());
+            prop_assert!(error.diagnostic().lines().last().unwrap().ends_with('^'));
+        }
+    }
+}
+
+/// Expressions over few variables, so that checking all of their assignments stays cheap
+fn small_expr() -> impl Strategy<Value = Expr<String>> {
+    let leaf = prop_oneof![constant().prop_map(Expr::Const), "[a-c]".prop_map(Expr::Var)];
+    leaf.prop_recursive(4, 16, 2, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|a| !a),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a & b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a | b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a ^ b),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| a.implies(b)),
+            (inner.clone(), inner).prop_map(|(a, b)| a.iff(b))
+        ]
+    })
+}
+
+fn is_nnf(expr: &Expr<String>) -> bool {
+    match expr {
+        Expr::Const(_) | Expr::Var(_) => true,
+        Expr::Not(inner) => matches!(**inner, Expr::Var(_)),
+        Expr::And(a, b) | Expr::Or(a, b) => is_nnf(a) && is_nnf(b),
+        _ => false
+    }
+}
+
+fn is_normal_form(expr: &Expr<String>, conjunctive: bool) -> bool {
+    let is_group = |expr: &Expr<String>| {
+        fn literals(expr: &Expr<String>, conjunctive: bool) -> bool {
+            match (expr, conjunctive) {
+                (Expr::Or(a, b), true) | (Expr::And(a, b), false) => literals(a, conjunctive) && literals(b, conjunctive),
+                (Expr::Const(_), _) | (Expr::Var(_), _) => true,
+                (Expr::Not(inner), _) => matches!(**inner, Expr::Var(_)),
+                _ => false
+            }
+        }
+        literals(expr, conjunctive)
+    };
+    match (expr, conjunctive) {
+        (Expr::And(a, b), true) | (Expr::Or(a, b), false) => is_normal_form(a, conjunctive) && is_normal_form(b, conjunctive),
+        _ => is_group(expr)
+    }
+}
+
+proptest! {
+    #[test]
+    fn simplification_preserves_meaning(expr in small_expr()) {
+        let simplified = expr.simplify();
+        prop_assert!(simplified.equivalent(&expr), "{} simplified to {}", expr, simplified);
+    }
+
+    #[test]
+    fn normal_forms_preserve_meaning(expr in small_expr()) {
+        let (nnf, cnf, dnf) = (expr.nnf(), expr.cnf(), expr.dnf());
+        prop_assert!(is_nnf(&nnf) && nnf.equivalent(&expr), "{} has negation normal form {}", expr, nnf);
+        prop_assert!(is_normal_form(&cnf, true) && cnf.equivalent(&expr), "{} has conjunctive normal form {}", expr, cnf);
+        prop_assert!(is_normal_form(&dnf, false) && dnf.equivalent(&expr), "{} has disjunctive normal form {}", expr, dnf);
+    }
+}
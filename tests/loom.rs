@@ -0,0 +1,92 @@
+//! Concurrency tests for `AtomicTriState` exploring every interleaving with loom.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+
+#![cfg(loom)]
+
+use loom::sync::Arc;
+use loom::thread;
+use std::sync::atomic::Ordering;
+use tristate::TriState;
+use tristate::atomic::AtomicTriState;
+
+#[test]
+fn concurrent_kleene_operations_are_atomic() {
+    loom::model(|| {
+        let flag = Arc::new(AtomicTriState::new(TriState::Default));
+
+        let and = {
+            let flag = flag.clone();
+            thread::spawn(move || flag.fetch_and(TriState::False, Ordering::AcqRel))
+        };
+        let or = {
+            let flag = flag.clone();
+            thread::spawn(move || flag.fetch_or(TriState::True, Ordering::AcqRel))
+        };
+        let (and, or) = (and.join().unwrap(), or.join().unwrap());
+
+        // Whichever runs second sees the result of the first, and ends with its own value
+        let last = flag.load(Ordering::Acquire);
+        match last {
+            TriState::False => assert_eq!((and, or), (TriState::True, TriState::Default)),
+            TriState::True => assert_eq!((and, or), (TriState::Default, TriState::False)),
+            TriState::Default => panic!("both operations were lost")
+        }
+    });
+}
+
+#[test]
+fn concurrent_negations_are_not_lost() {
+    loom::model(|| {
+        let flag = Arc::new(AtomicTriState::new(TriState::True));
+
+        let threads: Vec<_> = (0..2).map(|_| {
+            let flag = flag.clone();
+            thread::spawn(move || flag.fetch_not(Ordering::AcqRel))
+        }).collect();
+        let mut seen: Vec<TriState> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+        seen.sort();
+
+        assert_eq!(seen, [TriState::False, TriState::True]);
+        assert_eq!(flag.load(Ordering::Acquire), TriState::True);
+    });
+}
+
+#[test]
+fn compare_exchange_has_a_single_winner() {
+    loom::model(|| {
+        let flag = Arc::new(AtomicTriState::new(TriState::Default));
+
+        let threads: Vec<_> = [TriState::True, TriState::False].iter().map(|&value| {
+            let flag = flag.clone();
+            thread::spawn(move || {
+                flag.compare_exchange(TriState::Default, value, Ordering::AcqRel, Ordering::Acquire).is_ok()
+            })
+        }).collect();
+        let winners = threads.into_iter().map(|thread| thread.join().unwrap()).filter(|&won| won).count();
+
+        assert_eq!(winners, 1);
+        assert_ne!(flag.load(Ordering::Acquire), TriState::Default);
+    });
+}
+
+#[test]
+fn store_is_visible_to_acquiring_load() {
+    loom::model(|| {
+        let data = Arc::new(loom::sync::atomic::AtomicUsize::new(0));
+        let flag = Arc::new(AtomicTriState::new(TriState::Default));
+
+        let writer = {
+            let (data, flag) = (data.clone(), flag.clone());
+            thread::spawn(move || {
+                data.store(42, Ordering::Relaxed);
+                flag.store(TriState::True, Ordering::Release);
+            })
+        };
+
+        if flag.load(Ordering::Acquire) == TriState::True {
+            assert_eq!(data.load(Ordering::Relaxed), 42);
+        }
+        writer.join().unwrap();
+    });
+}
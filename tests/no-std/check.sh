@@ -0,0 +1,12 @@
+#!/bin/sh
+# Builds the crate for bare-metal targets without the standard library. The first target only
+# has atomic loads and stores, the second has full atomics. Install them with
+# `rustup target add thumbv6m-none-eabi thumbv7em-none-eabihf`.
+set -eu
+
+manifest="$(dirname "$0")/Cargo.toml"
+
+for target in thumbv6m-none-eabi thumbv7em-none-eabihf; do
+    cargo build --manifest-path "$manifest" --target "$target" --no-default-features
+    cargo build --manifest-path "$manifest" --target "$target" --no-default-features --features alloc,serde
+done
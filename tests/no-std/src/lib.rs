@@ -0,0 +1,38 @@
+//! Uses the parts of the crate that need neither `std` nor `alloc`.
+
+#![no_std]
+
+use core::convert::TryFrom;
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::Ordering;
+
+#[cfg(target_has_atomic = "8")]
+use tristate::atomic::AtomicTriState;
+use tristate::logic::{Logic, Lukasiewicz};
+use tristate::ternary::Tryte;
+use tristate::{Patch, TriState};
+
+#[cfg(target_has_atomic = "8")]
+static FLAG: AtomicTriState = AtomicTriState::new(TriState::Default);
+
+pub fn kleene(a: TriState, b: TriState) -> TriState {
+    (a & b) | !a.implies(b)
+}
+
+pub fn lukasiewicz(a: TriState, b: TriState) -> TriState {
+    Lukasiewicz::implies(a, b)
+}
+
+pub fn ternary(a: i64, b: i64) -> Option<i64> {
+    let product = Tryte::try_from(a).ok()?.checked_mul(Tryte::try_from(b).ok()?)?;
+    i64::try_from(product).ok()
+}
+
+pub fn patch(value: TriState, target: &mut Option<bool>) {
+    Patch::from(value).apply(target);
+}
+
+#[cfg(target_has_atomic = "8")]
+pub fn toggle() -> TriState {
+    FLAG.fetch_not(Ordering::AcqRel)
+}
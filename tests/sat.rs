@@ -0,0 +1,67 @@
+//! Property tests for the SAT solver against brute-force enumeration.
+
+#![cfg(feature = "alloc")]
+
+use proptest::prelude::*;
+use tristate::TriState;
+use tristate::sat::{Cnf, Lit, Solution, eval_clause};
+
+const MAX_VARS: usize = 8;
+
+fn cnf() -> impl Strategy<Value = Cnf> {
+    (1..=MAX_VARS).prop_flat_map(|num_vars| {
+        let lit = (0..num_vars, any::<bool>())
+            .prop_map(|(var, negative)| if negative { Lit::negative(var) } else { Lit::positive(var) });
+        prop::collection::vec(prop::collection::vec(lit, 1..=3), 0..40).prop_map(move |clauses| {
+            let mut cnf = Cnf::with_vars(num_vars);
+            for clause in clauses {
+                cnf.add_clause(clause);
+            }
+            cnf
+        })
+    })
+}
+
+fn assumptions() -> impl Strategy<Value = Vec<TriState>> {
+    let value = prop_oneof![Just(TriState::False), Just(TriState::Default), Just(TriState::True)];
+    prop::collection::vec(value, 0..=MAX_VARS)
+}
+
+/// Returns true if some assignment of every variable satisfies the formula and agrees with the
+/// definite assumptions
+fn brute_force(cnf: &Cnf, assumptions: &[TriState]) -> bool {
+    let num_vars = cnf.num_vars().max(assumptions.len());
+    (0..1u32 << num_vars).any(|bits| {
+        let assignment: Vec<TriState> = (0..num_vars).map(|var| TriState::from(bits >> var & 1 == 1)).collect();
+        let agrees = assumptions.iter().zip(&assignment).all(|(&assumed, &value)| assumed == TriState::Default || assumed == value);
+        agrees && cnf.eval(&assignment) == TriState::True
+    })
+}
+
+fn check(cnf: &Cnf, assumptions: &[TriState], solution: Solution) -> Result<(), TestCaseError> {
+    let expected = brute_force(cnf, assumptions);
+    prop_assert_eq!(solution.is_sat(), expected, "formula {:?} under {:?}", cnf.to_string(), assumptions);
+    if let Some(model) = solution.model() {
+        for clause in cnf.clauses() {
+            prop_assert_eq!(eval_clause(clause, &model), TriState::True, "clause {:?} in model {:?}", clause, model);
+        }
+        for (var, &assumed) in assumptions.iter().enumerate() {
+            if assumed != TriState::Default {
+                prop_assert_eq!(model[var], assumed, "variable {} in model {:?}", var, model);
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn solve_agrees_with_brute_force(cnf in cnf()) {
+        check(&cnf, &[], cnf.solve())?;
+    }
+
+    #[test]
+    fn solve_under_agrees_with_brute_force(cnf in cnf(), assumptions in assumptions()) {
+        check(&cnf, &assumptions, cnf.solve_under(&assumptions))?;
+    }
+}
@@ -0,0 +1,141 @@
+//! Derive macros for the [`tristate`](https://docs.rs/tristate) crate.
+//!
+//! These are re-exported by `tristate` when its `derive` feature is enabled, and should be used
+//! through that crate rather than depended on directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+/// Derives `TriMerge` for a structure whose named fields all implement `TriMerge`
+///
+/// Alongside the implementation this generates the resolved form of the structure, named
+/// `Resolved` followed by the structure's name, with every `TriState` field replaced by a `bool`
+/// and every nested field replaced by its own resolved form. A different name can be chosen
+/// with `#[tri_merge(resolved = "Name")]`.
+///
+/// ```rust
+/// use tristate::{TriMerge, TriState};
+///
+/// #[derive(TriMerge, Default)]
+/// struct Network {
+///     ipv6: TriState,
+///     tls: TriState
+/// }
+///
+/// #[derive(TriMerge, Default)]
+/// #[tri_merge(resolved = "Settings")]
+/// struct Config {
+///     color: TriState,
+///     network: Network
+/// }
+///
+/// let mut config = Config { color: TriState::True, ..Config::default() };
+/// config.overlay(&Config { color: TriState::False, ..Config::default() });
+/// config.underlay(&Config {
+///     network: Network { ipv6: TriState::True, ..Network::default() },
+///     ..Config::default()
+/// });
+///
+/// assert_eq!(config.color, TriState::False);
+/// assert_eq!(config.unresolved(), vec!["network.tls".to_owned()]);
+///
+/// let settings: Settings = config.resolve(&Settings {
+///     color: true,
+///     network: ResolvedNetwork { ipv6: false, tls: true }
+/// });
+/// assert_eq!(settings, Settings { color: false, network: ResolvedNetwork { ipv6: true, tls: true } });
+/// ```
+#[proc_macro_derive(TriMerge, attributes(tri_merge))]
+pub fn derive_tri_merge(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tri_merge(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand_tri_merge(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "TriMerge cannot be derived for generic structs"));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "TriMerge can only be derived for structs with named fields"))
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "TriMerge can only be derived for structs"))
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let resolved = resolved_name(&input)?;
+    let resolved_doc = format!("The resolved form of [`{}`]", name);
+
+    let idents: Vec<&Ident> = fields.iter().filter_map(|field| field.ident.as_ref()).collect();
+    let names: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    let resolved_fields = fields.iter().map(|field| {
+        let docs = field.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+        let (vis, ident, ty) = (&field.vis, &field.ident, &field.ty);
+        quote! {
+            #(#docs)*
+            #vis #ident: <#ty as ::tristate::TriMerge>::Resolved
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #resolved_doc]
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+        #vis struct #resolved {
+            #(#resolved_fields,)*
+        }
+
+        impl ::tristate::TriMerge for #name {
+            type Resolved = #resolved;
+
+            fn overlay(&mut self, other: &Self) {
+                #(::tristate::TriMerge::overlay(&mut self.#idents, &other.#idents);)*
+            }
+
+            fn underlay(&mut self, other: &Self) {
+                #(::tristate::TriMerge::underlay(&mut self.#idents, &other.#idents);)*
+            }
+
+            fn resolve(&self, defaults: &Self::Resolved) -> Self::Resolved {
+                #resolved {
+                    #(#idents: ::tristate::TriMerge::resolve(&self.#idents, &defaults.#idents),)*
+                }
+            }
+
+            fn unresolved_into(
+                &self,
+                prefix: &str,
+                out: &mut ::tristate::__private::Vec<::tristate::__private::String>
+            ) {
+                #({
+                    let path = if prefix.is_empty() {
+                        ::tristate::__private::String::from(#names)
+                    } else {
+                        ::tristate::__private::format!("{}.{}", prefix, #names)
+                    };
+                    ::tristate::TriMerge::unresolved_into(&self.#idents, &path, out);
+                })*
+            }
+        }
+    })
+}
+
+/// Returns the name of the resolved struct, from `#[tri_merge(resolved = "...")]` if present
+fn resolved_name(input: &DeriveInput) -> syn::Result<Ident> {
+    let mut name = format_ident!("Resolved{}", input.ident);
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("tri_merge")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("resolved") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = value.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported tri_merge attribute"))
+            }
+        })?;
+    }
+    Ok(name)
+}